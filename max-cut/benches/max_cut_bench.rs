@@ -1,90 +1,193 @@
-use std::{fs, sync::Arc};
+use std::{fs, path::Path, time::Duration};
 
 use criterion::{
     criterion_group, criterion_main,
-    BenchmarkId, Criterion, PlotConfiguration, AxisScale,
+    BenchmarkGroup, BenchmarkId, Criterion, PlotConfiguration, AxisScale, Throughput,
+    measurement::WallTime,
 };
-use max_cut::*;
+use max_cut::{graph::Graph, solver::Algorithm, *};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+const SEED: u64 = 41;
+
+/// Algorithms that run on every graph in polynomial time.
+const HEURISTICS: [Algorithm; 11] = [
+    Algorithm::Greedy,
+    Algorithm::GreedyImproved,
+    Algorithm::Random,
+    Algorithm::RandomImproved,
+    Algorithm::RandomParallel,
+    Algorithm::Derandomized,
+    Algorithm::Spectral,
+    Algorithm::Trevisan,
+    Algorithm::Oct { max_exact: 4 },
+    Algorithm::Treewidth { max_width: 8 },
+    Algorithm::GreedyOrdered {
+        order: approx::VertexOrder::SmallestLast,
+        passes: 3,
+    },
+];
+
+fn id(graph: &Graph) -> String {
+    format!("vertices: {}, edges: {}", graph.size(), graph.edge_size())
+}
 
-pub fn approx_bench(c: &mut Criterion) {
-    let mut time_group = c.benchmark_group("approx_bench");
+fn configure(group: &mut BenchmarkGroup<WallTime>, graph: &Graph) {
+    group.throughput(Throughput::Elements((graph.size() + graph.edge_size()) as u64));
+    group.sample_size(10);
+    group.warm_up_time(Duration::from_millis(500));
+    group.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
+}
 
-    let graphs = fs::read_dir("benches/data/vc_exact").unwrap();
+fn bench_algorithm(group: &mut BenchmarkGroup<WallTime>, algorithm: Algorithm, parameter: &str, graph: &Graph) {
+    group.bench_with_input(BenchmarkId::new(algorithm.to_string(), parameter), graph, |b, g| {
+        b.iter(|| algorithm.solve_seeded(g, SEED).unwrap())
+    });
+}
 
-    for graph_path in graphs {
-        let graph_path = graph_path.unwrap().path();
-        let raw = fs::read_to_string(&graph_path).unwrap();
-        let graph = graph_parser::parse_pace_graph(&raw).unwrap();
+/// Grid with `extra` seeded edges inside the color classes, so it has an odd
+/// cycle transversal of at most `extra` vertices.
+fn near_bipartite(rows: usize, columns: usize, extra: usize) -> Graph {
+    let mut graph = generate::grid(rows, columns);
+    let mut rng = SmallRng::seed_from_u64(SEED);
+    let color = |vertex: usize| (vertex / columns + vertex % columns) % 2;
+    let mut added = 0;
+
+    while added < extra {
+        let (u, v) = (rng.gen_range(0..graph.size()), rng.gen_range(0..graph.size()));
+
+        if u != v && color(u) == color(v) && !graph.contains_edge(&(u, v)) {
+            graph.add_edge(&(u, v));
+            added += 1;
+        }
+    }
 
-        let id = format!(
-            "graph: {}, vetices: {}, edges: {}",
-            graph_path.file_name().unwrap().to_str().unwrap(),
-            graph.size(),
-            graph.edge_size()
-        );
+    graph
+}
+
+/// Parsing of generated graphs in both formats.
+pub fn parse_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+
+    for n in [1_000, 4_000, 16_000] {
+        let graph = generate::gnm(n, 8 * n, SEED);
+        let pace = graph_writer::write_pace_graph(&graph);
+        let rudy = graph_writer::write_rudy(&graph);
 
-        time_group.throughput(criterion::Throughput::Elements((graph.size() + graph.edge_size()) as u64));
-        time_group.sample_size(20);
-        // time_group.measurement_time(Duration::from_millis(1));
-        // time_group.warm_up_time(Duration::from_millis(1));
-        time_group.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
-        time_group.bench_with_input(BenchmarkId::new("approx basic", &id), &graph, |b, g| {
-            b.iter(|| approx::max_cut_greedy(g))
+        configure(&mut group, &graph);
+        group.throughput(Throughput::Bytes(pace.len() as u64));
+        group.bench_with_input(BenchmarkId::new("pace", id(&graph)), &pace, |b, raw| {
+            b.iter(|| graph_parser::parse_pace_graph(raw).unwrap())
         });
-        time_group.bench_with_input(BenchmarkId::new("approx improved", &id), &graph, |b, g| {
-            b.iter(|| approx::max_cut_greedy_impr(g))
+        group.throughput(Throughput::Bytes(rudy.len() as u64));
+        group.bench_with_input(BenchmarkId::new("rudy", id(&graph)), &rudy, |b, raw| {
+            b.iter(|| graph_parser::parse_rudy(raw).unwrap())
         });
     }
 
-    time_group.finish();
+    group.finish();
 }
 
-pub fn max_cut_bench(c: &mut Criterion) {
-    let mut time_group = c.benchmark_group("heuristic_approx_bench");
+/// Building a [`Graph`] from an edge list.
+pub fn construction_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("construction");
+
+    for n in [1_000, 4_000, 16_000] {
+        let graph = generate::gnm(n, 8 * n, SEED);
+        let edges: Vec<(usize, usize)> = graph.all_edges().iter().map(|e| (e.0, e.1)).collect();
+
+        configure(&mut group, &graph);
+        group.bench_with_input(BenchmarkId::new("add_edges", id(&graph)), &edges, |b, edges| {
+            b.iter(|| {
+                let mut graph = Graph::new_empty(n);
+                graph.add_edges(edges);
+                graph
+            })
+        });
+    }
 
-    let graphs = fs::read_dir("benches/data/vc_exact").unwrap();
+    group.finish();
+}
 
-    for graph_path in graphs {
-        let graph_path = graph_path.unwrap().path();
-        let raw = fs::read_to_string(&graph_path).unwrap();
-        let graph = Arc::new(graph_parser::parse_pace_graph(&raw).unwrap());
+/// Heuristics on sparse random graphs of growing size with mean degree 16.
+pub fn size_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("size_scaling");
 
-        let id = format!(
-            "graph: {}, vetices: {}, edges: {}",
-            graph_path.file_name().unwrap().to_str().unwrap(),
-            graph.size(),
-            graph.edge_size()
+    for n in [250, 1_000, 4_000] {
+        let graph = generate::gnm(n, 8 * n, SEED);
+        configure(&mut group, &graph);
+
+        for algorithm in HEURISTICS {
+            bench_algorithm(&mut group, algorithm, &id(&graph), &graph);
+        }
+    }
+
+    group.finish();
+}
+
+/// Heuristics on random graphs with 500 vertices of growing density.
+pub fn density_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("density_scaling");
+
+    for p in [0.01, 0.05, 0.2, 0.5] {
+        let graph = generate::gnp(500, p, SEED);
+        configure(&mut group, &graph);
+
+        for algorithm in HEURISTICS {
+            bench_algorithm(&mut group, algorithm, &format!("p: {p}, {}", id(&graph)), &graph);
+        }
+    }
+
+    group.finish();
+}
+
+/// Exact algorithms on the graph families they are made for.
+pub fn exact_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("exact_scaling");
+
+    for n in [12, 16, 20] {
+        let graph = generate::gnp(n, 0.3, SEED);
+        configure(&mut group, &graph);
+        bench_algorithm(&mut group, Algorithm::BruteForce { max_size: n }, &id(&graph), &graph);
+        bench_algorithm(&mut group, Algorithm::Ilp, &id(&graph), &graph);
+    }
+
+    for side in [10, 20, 40] {
+        let grid = generate::grid(side, side);
+        configure(&mut group, &grid);
+        bench_algorithm(&mut group, Algorithm::Planar, &format!("grid {side}x{side}"), &grid);
+
+        let strip = generate::grid(4, side * side / 4);
+        configure(&mut group, &strip);
+        bench_algorithm(
+            &mut group,
+            Algorithm::Treewidth { max_width: 8 },
+            &format!("grid 4x{}", side * side / 4),
+            &strip,
         );
 
-        time_group.throughput(criterion::Throughput::Elements((graph.size() + graph.edge_size()) as u64));
-        time_group.sample_size(20);
-        // time_group.measurement_time(Duration::from_millis(1));
-        // time_group.warm_up_time(Duration::from_millis(1));
-        time_group.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
-        time_group.bench_with_input(BenchmarkId::new("approx basic", &id), &graph, |b, g| {
-            b.iter(|| approx::max_cut_greedy(g))
-        });
-        time_group.bench_with_input(BenchmarkId::new("approx improved", &id), &graph, |b, g| {
-            b.iter(|| approx::max_cut_greedy_impr(g))
-        });
-        time_group.bench_with_input(BenchmarkId::new("heuristic_basic", &id), &graph, |b, g| {
-            b.iter(|| heuristic::rand_aprox(g))
-        });
-        time_group.bench_with_input(BenchmarkId::new("heuristic_improved", &id), &graph, |b, g| {
-            b.iter(||  heuristic::rand_approx_impr(Arc::clone(g)))
-        });
-        time_group.bench_with_input(BenchmarkId::new("heuristic_parallel", &id), &graph, |b, g| {
-            b.iter(|| heuristic::rand_aprox_parallel(g))
-        });
+        let near_bipartite = near_bipartite(side, side, 8);
+        configure(&mut group, &near_bipartite);
+        bench_algorithm(
+            &mut group,
+            Algorithm::Oct { max_exact: 12 },
+            &format!("grid {side}x{side} with 8 odd edges"),
+            &near_bipartite,
+        );
     }
 
-    time_group.finish();
+    group.finish();
 }
 
-pub fn heuristic_approx_bench(c: &mut Criterion) {
-    let mut time_group = c.benchmark_group("heuristic_approx_bench");
+/// Heuristics on the instances in `benches/data/vc_exact`, skipped if they are missing.
+pub fn instance_bench(c: &mut Criterion) {
+    let directory = Path::new("benches/data/vc_exact");
+    let Ok(graphs) = fs::read_dir(directory) else {
+        eprintln!("skipping instance benchmarks, '{}' is missing", directory.display());
+        return;
+    };
 
-    let graphs = fs::read_dir("benches/data/vc_exact").unwrap();
+    let mut group = c.benchmark_group("instances");
 
     for graph_path in graphs {
         let graph_path = graph_path.unwrap().path();
@@ -98,21 +201,23 @@ pub fn heuristic_approx_bench(c: &mut Criterion) {
             graph.edge_size()
         );
 
-        time_group.throughput(criterion::Throughput::Elements((graph.size() + graph.edge_size()) as u64));
-        time_group.sample_size(20);
-        // time_group.measurement_time(Duration::from_secs(1));
-        // time_group.warm_up_time(Duration::from_millis(1));
-        time_group.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
-        time_group.bench_with_input(BenchmarkId::new("heuristic_parallel", &id), &graph, |b, g| {
-            b.iter(|| heuristic::rand_aprox(g))
-        });
-        time_group.bench_with_input(BenchmarkId::new("approx_improved", &id), &graph, |b, g| {
-            b.iter(|| approx::max_cut_greedy_impr(g))
-        });
+        configure(&mut group, &graph);
+
+        for algorithm in &HEURISTICS[..5] {
+            bench_algorithm(&mut group, *algorithm, &id, &graph);
+        }
     }
 
-    time_group.finish();
+    group.finish();
 }
 
-criterion_group!(time, max_cut_bench);
+criterion_group!(
+    time,
+    parse_bench,
+    construction_bench,
+    size_bench,
+    density_bench,
+    exact_bench,
+    instance_bench
+);
 criterion_main!(time);
@@ -1,39 +1,44 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt;
+
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
 use crate::graph::{Edge, Graph};
 
+/// Puts every vertex into `S` unless more of its neighbors are in `S` than
+/// outside, where vertices not placed yet count as outside.
+///
+/// Runs in `O(n + m)`: `S` is a bitset and the cut value is updated with every
+/// vertex added, so the cut is collected in one pass over the edges.
 pub fn max_cut_greedy(graph: &Graph) -> Vec<Edge> {
-    let mut s = vec![];
+    let mut s = Bitset::new(graph.size());
+    let mut value = 0;
 
     for vertex in 0..graph.size() {
-        let neighbors = graph.get_neighbors(vertex);
-        let counts = neighbors
-            .iter()
-            .fold((0usize, 0usize), |mut counts, neigh| {
-                if s.contains(neigh) {
-                    counts.0 += 1;
-                } else {
-                    counts.1 += 1;
-                }
-
-                counts
-            });
+        let neighbors = graph.neighbors(vertex);
+        let inside = neighbors.iter().filter(|neigh| s.contains(**neigh)).count();
+        let outside = neighbors.len() - inside;
 
-        if counts.0 <= counts.1 {
-            s.push(vertex);
+        if inside <= outside {
+            s.insert(vertex);
+            // Edges into S become uncut, the others cut, except for self-loops
+            let loops = neighbors.iter().filter(|neigh| **neigh == vertex).count();
+            value = value + outside - loops - inside;
         }
     }
 
-    let edges = graph.all_edges();
-    let mut cut: Vec<Edge> = Vec::new();
+    let mut cut = Vec::with_capacity(value);
 
-    for edge in edges {
-        if s.contains(&edge.0) == s.contains(&edge.1)
-        {
-            continue;
-        } else {
+    for edge in graph.all_edges() {
+        if s.contains(edge.0) != s.contains(edge.1) {
             cut.push(edge);
         }
     }
 
+    debug_assert_eq!(cut.len(), value);
     cut
 }
 
@@ -66,3 +71,342 @@ pub fn max_cut_greedy_impr(graph: &Graph) -> Vec<Edge> {
     }
     cut
 }
+
+/// Set of vertices stored as one bit per vertex.
+struct Bitset {
+    words: Vec<u64>,
+}
+
+impl Bitset {
+    fn new(size: usize) -> Bitset {
+        Bitset {
+            words: vec![0; size.div_ceil(64)],
+        }
+    }
+
+    fn contains(&self, vertex: usize) -> bool {
+        self.words[vertex / 64] >> (vertex % 64) & 1 == 1
+    }
+
+    fn insert(&mut self, vertex: usize) {
+        self.words[vertex / 64] |= 1 << (vertex % 64);
+    }
+}
+
+/// Order in which [`max_cut_greedy_ordered`] places the vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VertexOrder {
+    /// 0, 1, ..., n - 1 like [`max_cut_greedy_impr`]
+    #[default]
+    Index,
+    /// Uniformly random, given by the seed of [`VertexOrder::order`]
+    Random,
+    DegreeDescending,
+    DegreeAscending,
+    /// Breadth first search, starting every component at its lowest vertex
+    Bfs,
+    /// Depth first search, starting every component at its lowest vertex
+    Dfs,
+    /// Reverse of repeatedly removing a vertex of minimum degree, so every
+    /// vertex has few neighbors placed after it
+    SmallestLast,
+    /// Descending by the sum of the degrees of the neighbors
+    WeightedDegree,
+}
+
+impl VertexOrder {
+    pub fn name(&self) -> &'static str {
+        match self {
+            VertexOrder::Index => "index",
+            VertexOrder::Random => "random",
+            VertexOrder::DegreeDescending => "degree-descending",
+            VertexOrder::DegreeAscending => "degree-ascending",
+            VertexOrder::Bfs => "bfs",
+            VertexOrder::Dfs => "dfs",
+            VertexOrder::SmallestLast => "smallest-last",
+            VertexOrder::WeightedDegree => "weighted-degree",
+        }
+    }
+
+    /// All vertices of `graph` in this order. Only [`VertexOrder::Random`]
+    /// uses `seed`, ties are broken by the lower vertex.
+    pub fn order(&self, graph: &Graph, seed: u64) -> Vec<usize> {
+        let mut vertices: Vec<usize> = (0..graph.size()).collect();
+        let degree = |vertex: &usize| graph.neighbors(*vertex).len();
+
+        match self {
+            VertexOrder::Index => {}
+            VertexOrder::Random => vertices.shuffle(&mut SmallRng::seed_from_u64(seed)),
+            VertexOrder::DegreeDescending => vertices.sort_by_key(|vertex| Reverse(degree(vertex))),
+            VertexOrder::DegreeAscending => vertices.sort_by_key(degree),
+            VertexOrder::Bfs => vertices = search_order(graph, false),
+            VertexOrder::Dfs => vertices = search_order(graph, true),
+            VertexOrder::SmallestLast => vertices = smallest_last(graph),
+            VertexOrder::WeightedDegree => vertices.sort_by_key(|vertex| {
+                Reverse(graph.neighbors(*vertex).iter().map(degree).sum::<usize>())
+            }),
+        }
+
+        vertices
+    }
+}
+
+impl fmt::Display for VertexOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Greedy of [`max_cut_greedy_impr`] placing the vertices in `order`, every
+/// vertex goes to the side opposite of most of its placed neighbors.
+/// Afterwards up to `passes` times every vertex in `order` is moved to the
+/// other side if most of all its neighbors are on its side, like the algorithm
+/// of Sahni and Gonzalez. Stops early once a pass moves no vertex.
+///
+/// The cut contains at least half of the edges.
+pub fn max_cut_greedy_ordered(graph: &Graph, order: &[usize], passes: usize) -> Vec<Edge> {
+    let mut placed = vec![false; graph.size()];
+    let mut sides = vec![false; graph.size()];
+
+    for &vertex in order {
+        let (mut same, mut other) = (0, 0);
+
+        for neigh in graph.neighbors(vertex) {
+            if !placed[*neigh] {
+                continue;
+            }
+            if sides[*neigh] {
+                same += 1;
+            } else {
+                other += 1;
+            }
+        }
+
+        sides[vertex] = same < other;
+        placed[vertex] = true;
+    }
+
+    local_search(graph, &mut sides, order, passes);
+    graph.cut(&sides)
+}
+
+/// Moves every vertex in `order` to the other side if most of its neighbors
+/// are on its side, for up to `max_passes` passes over `order`. Stops early
+/// once a pass moves no vertex, then every vertex in `order` has at least half
+/// of its edges cut.
+pub fn local_search(graph: &Graph, sides: &mut [bool], order: &[usize], max_passes: usize) {
+    for _ in 0..max_passes {
+        let mut moved = false;
+
+        for &vertex in order {
+            // Self-loops stay uncut on either side
+            let (same, other) = graph
+                .neighbors(vertex)
+                .iter()
+                .filter(|neigh| **neigh != vertex)
+                .fold((0, 0), |(same, other), neigh| {
+                    if sides[*neigh] == sides[vertex] {
+                        (same + 1, other)
+                    } else {
+                        (same, other + 1)
+                    }
+                });
+
+            if same > other {
+                sides[vertex] = !sides[vertex];
+                moved = true;
+            }
+        }
+
+        if !moved {
+            break;
+        }
+    }
+}
+
+/// Breadth or depth first search order of all components.
+fn search_order(graph: &Graph, depth_first: bool) -> Vec<usize> {
+    let mut visited = vec![false; graph.size()];
+    let mut order = Vec::with_capacity(graph.size());
+    let mut pending = VecDeque::new();
+
+    for root in 0..graph.size() {
+        if visited[root] {
+            continue;
+        }
+
+        pending.push_back(root);
+
+        while let Some(vertex) = if depth_first {
+            pending.pop_back()
+        } else {
+            pending.pop_front()
+        } {
+            if std::mem::replace(&mut visited[vertex], true) {
+                continue;
+            }
+
+            order.push(vertex);
+
+            let neighbors = graph.neighbors(vertex).iter().filter(|neigh| !visited[**neigh]);
+
+            // Depth first search takes the last one first, so it visits the lower neighbors first
+            if depth_first {
+                pending.extend(neighbors.rev());
+            } else {
+                pending.extend(neighbors);
+            }
+        }
+    }
+
+    order
+}
+
+/// Smallest last order by removing vertices from buckets of their degree.
+fn smallest_last(graph: &Graph) -> Vec<usize> {
+    let n = graph.size();
+    // Self-loops don't count, so every degree is below `n`
+    let mut degrees: Vec<usize> = (0..n)
+        .map(|vertex| graph.neighbors(vertex).iter().filter(|neigh| **neigh != vertex).count())
+        .collect();
+    let mut buckets: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); n.max(1)];
+    let mut removed = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    let mut min = 0usize;
+
+    for (vertex, degree) in degrees.iter().enumerate() {
+        buckets[*degree].insert(vertex);
+    }
+
+    for _ in 0..n {
+        // Removing a vertex lowers the minimum degree by at most one
+        min = min.saturating_sub(1);
+        while buckets[min].is_empty() {
+            min += 1;
+        }
+
+        let vertex = buckets[min].pop_first().unwrap();
+        removed[vertex] = true;
+        order.push(vertex);
+
+        for neigh in graph.neighbors(vertex) {
+            if !removed[*neigh] {
+                buckets[degrees[*neigh]].remove(neigh);
+                degrees[*neigh] -= 1;
+                buckets[degrees[*neigh]].insert(*neigh);
+            }
+        }
+    }
+
+    order.reverse();
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+
+    const ORDERS: [VertexOrder; 8] = [
+        VertexOrder::Index,
+        VertexOrder::Random,
+        VertexOrder::DegreeDescending,
+        VertexOrder::DegreeAscending,
+        VertexOrder::Bfs,
+        VertexOrder::Dfs,
+        VertexOrder::SmallestLast,
+        VertexOrder::WeightedDegree,
+    ];
+
+    /// The former `O(n m)` implementation of [`max_cut_greedy`].
+    fn greedy_with_list(graph: &Graph) -> Vec<Edge> {
+        let mut s = vec![];
+
+        for vertex in 0..graph.size() {
+            let inside = graph.neighbors(vertex).iter().filter(|neigh| s.contains(*neigh)).count();
+
+            if 2 * inside <= graph.neighbors(vertex).len() {
+                s.push(vertex);
+            }
+        }
+
+        graph
+            .all_edges()
+            .into_iter()
+            .filter(|edge| s.contains(&edge.0) != s.contains(&edge.1))
+            .collect()
+    }
+
+    #[test]
+    fn greedy_is_unchanged() {
+        for seed in 0..20 {
+            let graph = generate::gnp(40 + 5 * seed as usize, 0.15, seed);
+            assert_eq!(max_cut_greedy(&graph), greedy_with_list(&graph));
+        }
+
+        // Self-loops count as neighbors outside of S but are never cut
+        let mut graph = Graph::new_empty(4);
+        graph.add_edges(&[(0, 0), (0, 1), (1, 1), (1, 2), (2, 3), (3, 3)]);
+        assert_eq!(max_cut_greedy(&graph), greedy_with_list(&graph));
+    }
+
+    #[test]
+    fn orders() {
+        // Path 0 - 1 - 2 - 3 and triangle 4, 5, 6 with pendant 7 at 6
+        let mut graph = Graph::new_empty(8);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 3), (4, 5), (5, 6), (4, 6), (6, 7)]);
+
+        assert_eq!(VertexOrder::Bfs.order(&graph, 0), vec![0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(VertexOrder::Dfs.order(&graph, 0), vec![0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(VertexOrder::DegreeDescending.order(&graph, 0), vec![6, 1, 2, 4, 5, 0, 3, 7]);
+        assert_eq!(VertexOrder::WeightedDegree.order(&graph, 0)[..2], [4, 5]);
+        // The triangle is the only part with minimum degree 2
+        let smallest_last = VertexOrder::SmallestLast.order(&graph, 0);
+        assert_eq!(smallest_last.len(), 8);
+        assert!([4, 5, 6].contains(&smallest_last[0]));
+
+        for order in ORDERS {
+            let mut vertices = order.order(&graph, 47);
+            vertices.sort();
+            assert_eq!(vertices, (0..8).collect::<Vec<_>>(), "{order}");
+        }
+    }
+
+    #[test]
+    fn self_loops_are_ignored() {
+        // Vertex 0 is looped and adjacent to all others, vertex 3 is only looped
+        let mut graph = Graph::new_empty(4);
+        graph.add_edges(&[(0, 1), (0, 2), (0, 0), (3, 3)]);
+
+        for order in ORDERS {
+            let vertices = order.order(&graph, 47);
+            assert_eq!(max_cut_greedy_ordered(&graph, &vertices, usize::MAX).len(), 2, "{order}");
+        }
+    }
+
+    #[test]
+    fn ordered_greedy() {
+        let graph = generate::gnp(80, 0.1, 47);
+        let index = VertexOrder::Index.order(&graph, 0);
+
+        assert_eq!(
+            max_cut_greedy_ordered(&graph, &index, 0).len(),
+            max_cut_greedy_impr(&graph).len()
+        );
+
+        for order in ORDERS {
+            let vertices = order.order(&graph, 47);
+            let greedy = max_cut_greedy_ordered(&graph, &vertices, 0);
+            let passes = max_cut_greedy_ordered(&graph, &vertices, usize::MAX);
+            let sides = graph.partition(&passes).unwrap();
+
+            assert!(2 * greedy.len() >= graph.edge_size());
+            assert!(passes.len() >= greedy.len());
+            // No single vertex can be moved to improve the cut
+            assert!((0..graph.size()).all(|vertex| {
+                let same = graph.neighbors(vertex).iter().filter(|neigh| sides[**neigh] == sides[vertex]).count();
+                2 * same <= graph.neighbors(vertex).len()
+            }));
+        }
+    }
+}
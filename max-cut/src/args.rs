@@ -1,34 +1,424 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::builder::RangedU64ValueParser;
+use clap::{Parser, Subcommand, ValueEnum};
+use max_cut::{approx::VertexOrder, bipartite, solver::Algorithm, treewidth};
+
+use crate::batch::BatchFormat;
+use crate::output::OutputFormat;
+use crate::progress::ProgressFormat;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Calculate a maximum cut of a graph
+    Solve(SolveArgs),
+    /// Print the size of the cut and the running time for every graph and algorithm
+    Bench(BenchArgs),
+    /// Run algorithms on many instances in parallel and collect the results in one file
+    Batch(BatchArgs),
+    /// Compare the cut values of algorithms over many seeds
+    Quality(QualityArgs),
+    /// Convert a graph, the format is given by the extension: '.gr' for PACE, else rudy
+    Convert {
+        /// Input graph
+        input: PathBuf,
+        /// Output graph
+        output: PathBuf,
+    },
+    /// Generate a random graph
+    Generate {
+        #[command(subcommand)]
+        model: Model,
+    },
+    /// Print statistics about a graph
+    Stats {
+        /// Input graph
+        file: PathBuf,
+    },
+    /// Check a solution file against a graph
+    Verify {
+        /// Input graph
+        file: PathBuf,
+        /// Solution file
+        solution: PathBuf,
+    },
+}
+
+#[derive(clap::Args)]
+pub struct SolveArgs {
     /// Input graph
-    pub file: std::path::PathBuf,
+    pub file: PathBuf,
 
-    /// Calculate the maximum cut using an integer with timeout.
-    /// If set to 0 no timeout is used.
-    #[arg(long, default_value_t = -1)]
-    pub ilp: i64,
+    #[command(flatten)]
+    pub algorithms: AlgorithmArgs,
 
-    /// Calculate the maximum cut using the approximation algorithm
-    #[arg(short, long)]
-    pub approx: bool,
+    #[command(flatten)]
+    pub preprocessing: Preprocessing,
+
+    #[command(flatten)]
+    pub progress: ProgressArgs,
+
+    /// Reference file of best known cut values to report the gap to,
+    /// the format is described in 'src/best_known.rs'
+    #[arg(short = 'k', long, value_name = "FILE")]
+    pub best_known: Option<PathBuf>,
+
+    /// Format of the results
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output_format: OutputFormat,
+
+    /// Write the results to FILE instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Write a solution file that can be checked with 'verify'.
+    /// Only possible with a single algorithm
+    #[arg(short, long, value_name = "FILE")]
+    pub solution: Option<PathBuf>,
+
+    /// Write the graph with the cut as Graphviz DOT.
+    /// Only possible with a single algorithm
+    #[arg(long, value_name = "FILE")]
+    pub dot: Option<PathBuf>,
+
+    /// Draw the graph with the cut as SVG, only practical for small graphs.
+    /// Only possible with a single algorithm
+    #[arg(long, value_name = "FILE")]
+    pub svg: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct BenchArgs {
+    /// Input graphs
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    #[command(flatten)]
+    pub algorithms: AlgorithmArgs,
+
+    #[command(flatten)]
+    pub preprocessing: Preprocessing,
+
+    #[command(flatten)]
+    pub progress: ProgressArgs,
+
+    /// Reference file of best known cut values to report the gap to,
+    /// the format is described in 'src/best_known.rs'
+    #[arg(short = 'k', long, value_name = "FILE")]
+    pub best_known: Option<PathBuf>,
+
+    /// Format of the results, 'text' prints CSV lines without header
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output_format: OutputFormat,
+
+    /// Write the results to FILE instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct BatchArgs {
+    /// Instance files, directories or patterns like 'data/rudy/g05_*'
+    #[arg(required = true)]
+    pub inputs: Vec<PathBuf>,
+
+    #[command(flatten)]
+    pub algorithms: AlgorithmArgs,
+
+    #[command(flatten)]
+    pub preprocessing: Preprocessing,
+
+    /// Reference file of best known cut values to report the gap to,
+    /// the format is described in 'src/best_known.rs'
+    #[arg(short = 'k', long, value_name = "FILE")]
+    pub best_known: Option<PathBuf>,
+
+    /// Number of runs at the same time [default: number of cores]
+    #[arg(short, long, value_name = "JOBS")]
+    pub jobs: Option<usize>,
+
+    /// Kill every run after SECONDS
+    #[arg(long, value_name = "SECONDS")]
+    pub time_limit: Option<u64>,
+
+    /// Format of the results
+    #[arg(long, value_enum, default_value_t = BatchFormat::Csv)]
+    pub output_format: BatchFormat,
+
+    /// Append the results to FILE, runs already in it are skipped
+    #[arg(short, long, value_name = "FILE")]
+    pub output: PathBuf,
+}
+
+#[derive(clap::Args)]
+pub struct QualityArgs {
+    /// Input graphs
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    #[command(flatten)]
+    pub algorithms: AlgorithmArgs,
+
+    /// Number of runs of every algorithm, each with its own seed
+    #[arg(long, default_value_t = 10)]
+    pub seeds: u64,
 
-    /// Calculate the maximum cut using the heuristic
-    #[arg(short = 'H', long)]
-    pub heuristic: bool,
+    /// Seed of the first run, the following runs count up from it
+    #[arg(long, default_value_t = 0)]
+    pub first_seed: u64,
 
-    /// Calculate the maximum cut using the paralell heuristic
+    /// Cut value a run has to reach to count as a success. Only possible with
+    /// a single graph [default: best known value or best cut of all runs]
+    #[arg(long, value_name = "VALUE")]
+    pub target: Option<usize>,
+
+    /// Reference file of best known cut values to report the gap to,
+    /// the format is described in 'src/best_known.rs'
+    #[arg(short = 'k', long, value_name = "FILE")]
+    pub best_known: Option<PathBuf>,
+
+    /// Print CSV instead of a table
     #[arg(long)]
-    pub heuristic_parallel: bool,
+    pub csv: bool,
+}
+
+#[derive(clap::Args)]
+pub struct ProgressArgs {
+    /// Report new cuts, bounds and restarts of the algorithms while they run
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub progress: Option<ProgressFormat>,
+
+    /// Write the progress to FILE instead of stderr
+    #[arg(long, value_name = "FILE", requires = "progress")]
+    pub progress_file: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct AlgorithmArgs {
+    /// Algorithm to run, can be given multiple times
+    #[arg(short, long = "algorithm", value_enum, required = true)]
+    pub algorithms: Vec<AlgorithmName>,
+
+    /// Stop the ILP after SECONDS and report the best cut found so far as not exact
+    #[arg(long, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+
+    /// Order in which 'greedy-ordered' places the vertices [default: index]
+    #[arg(long, value_enum, value_name = "ORDER")]
+    pub order: Option<OrderName>,
+
+    /// Most passes of 'greedy-ordered' moving single vertices to the other side [default: 0]
+    #[arg(long, value_name = "PASSES")]
+    pub passes: Option<usize>,
+
+    /// Largest odd cycle transversal for which 'oct' tries all placements,
+    /// at most 20, as every vertex doubles the placements [default: 12]
+    #[arg(
+        long,
+        value_name = "MAX_EXACT",
+        value_parser = RangedU64ValueParser::<usize>::new().range(..=bipartite::EXACT_LIMIT as u64)
+    )]
+    pub max_exact: Option<usize>,
+
+    /// Largest tree decomposition width for which 'treewidth' runs the
+    /// dynamic program instead of the improved greedy algorithm, at most 24 [default: 16]
+    #[arg(
+        long,
+        value_name = "MAX_WIDTH",
+        value_parser = RangedU64ValueParser::<usize>::new().range(..=treewidth::WIDTH_LIMIT as u64)
+    )]
+    pub max_width: Option<usize>,
+
+    /// Largest graph 'brute-force' accepts [default: 30]
+    #[arg(long, value_name = "MAX_SIZE")]
+    pub max_size: Option<usize>,
+}
+
+impl AlgorithmArgs {
+    /// The chosen algorithms with their parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if a parameter is given without its algorithm.
+    pub fn algorithms(&self) -> Result<Vec<Algorithm>, String> {
+        let parameters = [
+            ("--timeout", self.timeout.is_some(), AlgorithmName::Ilp),
+            ("--order", self.order.is_some(), AlgorithmName::GreedyOrdered),
+            ("--passes", self.passes.is_some(), AlgorithmName::GreedyOrdered),
+            ("--max-exact", self.max_exact.is_some(), AlgorithmName::Oct),
+            ("--max-width", self.max_width.is_some(), AlgorithmName::Treewidth),
+            ("--max-size", self.max_size.is_some(), AlgorithmName::BruteForce),
+        ];
+
+        for (parameter, given, name) in parameters {
+            if given && !self.algorithms.contains(&name) {
+                return Err(format!(
+                    "'{parameter}' can only be used with '--algorithm {}'",
+                    name.to_possible_value().unwrap().get_name()
+                ));
+            }
+        }
+
+        Ok(self
+            .algorithms
+            .iter()
+            .map(|name| match name {
+                AlgorithmName::Ilp => Algorithm::Ilp,
+                AlgorithmName::Greedy => Algorithm::Greedy,
+                AlgorithmName::GreedyImproved => Algorithm::GreedyImproved,
+                AlgorithmName::GreedyOrdered => Algorithm::GreedyOrdered {
+                    order: self.order.map_or(VertexOrder::Index, OrderName::order),
+                    passes: self.passes.unwrap_or(0),
+                },
+                AlgorithmName::Random => Algorithm::Random,
+                AlgorithmName::RandomImproved => Algorithm::RandomImproved,
+                AlgorithmName::RandomParallel => Algorithm::RandomParallel,
+                AlgorithmName::Derandomized => Algorithm::Derandomized,
+                AlgorithmName::Spectral => Algorithm::Spectral,
+                AlgorithmName::Trevisan => Algorithm::Trevisan,
+                AlgorithmName::Oct => Algorithm::Oct {
+                    max_exact: self.max_exact.unwrap_or(12),
+                },
+                AlgorithmName::Planar => Algorithm::Planar,
+                AlgorithmName::Treewidth => Algorithm::Treewidth {
+                    max_width: self.max_width.unwrap_or(16),
+                },
+                AlgorithmName::BruteForce => Algorithm::BruteForce {
+                    max_size: self.max_size.unwrap_or(30),
+                },
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AlgorithmName {
+    /// Integer linear program, exact
+    Ilp,
+    /// Greedy approximation
+    Greedy,
+    /// Greedy approximation cutting at least half of the edges
+    GreedyImproved,
+    /// Improved greedy in a chosen vertex order with optional improving passes
+    GreedyOrdered,
+    /// Random bipartition
+    Random,
+    /// Random bipartitions until at least half of the edges are cut
+    RandomImproved,
+    /// Random bipartition chosen in parallel
+    RandomParallel,
+    /// Best of a small family of bipartitions, cutting at least half of the edges
+    Derandomized,
+    /// Sweep along the smallest eigenvector of the adjacency matrix
+    Spectral,
+    /// Recursive spectral partitioning cutting at least half of the edges
+    Trevisan,
+    /// Odd cycle transversal, exact for nearly bipartite graphs
+    Oct,
+    /// Planar dual and T-joins, exact for planar graphs
+    Planar,
+    /// Tree decomposition, exact for graphs of small treewidth
+    Treewidth,
+    /// All bipartitions, exact for small graphs
+    BruteForce,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OrderName {
+    /// Vertex index
+    Index,
+    /// Random, different every run
+    Random,
+    /// Highest degree first
+    DegreeDescending,
+    /// Lowest degree first
+    DegreeAscending,
+    /// Breadth first search
+    Bfs,
+    /// Depth first search
+    Dfs,
+    /// Every vertex has few neighbors after it
+    SmallestLast,
+    /// Highest sum of the neighbor degrees first
+    WeightedDegree,
+}
 
-    /// Benchmark the calculation
+impl OrderName {
+    fn order(self) -> VertexOrder {
+        match self {
+            OrderName::Index => VertexOrder::Index,
+            OrderName::Random => VertexOrder::Random,
+            OrderName::DegreeDescending => VertexOrder::DegreeDescending,
+            OrderName::DegreeAscending => VertexOrder::DegreeAscending,
+            OrderName::Bfs => VertexOrder::Bfs,
+            OrderName::Dfs => VertexOrder::Dfs,
+            OrderName::SmallestLast => VertexOrder::SmallestLast,
+            OrderName::WeightedDegree => VertexOrder::WeightedDegree,
+        }
+    }
+}
+
+#[derive(clap::Args)]
+pub struct Preprocessing {
+    /// Solve every biconnected component of the graph on its own
     #[arg(short, long)]
-    pub bench: bool,
+    pub decompose: bool,
 
-    /// Use the improved variant of the algorithm.
-    /// Can't be used together with '--ipl'
+    /// Cut bipartite components completely before running the algorithm
+    #[arg(short = 'B', long)]
+    pub bipartite: bool,
+
+    /// Shrink the graph with safe reduction rules before solving
     #[arg(short, long)]
-    pub improved: bool,
+    pub reduce: bool,
+}
+
+impl Preprocessing {
+    /// The chosen steps joined by '+', empty without preprocessing.
+    pub fn name(&self) -> String {
+        [("decompose", self.decompose), ("bipartite", self.bipartite), ("reduce", self.reduce)]
+            .iter()
+            .filter(|(_, set)| *set)
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Model {
+    /// Every edge is present with probability PROBABILITY
+    Gnp {
+        vertices: usize,
+        probability: f64,
+
+        #[command(flatten)]
+        target: GenerateArgs,
+    },
+    /// EDGES edges chosen uniformly at random
+    Gnm {
+        vertices: usize,
+        edges: usize,
+
+        #[command(flatten)]
+        target: GenerateArgs,
+    },
+}
+
+#[derive(clap::Args)]
+pub struct GenerateArgs {
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// Write the graph to FILE instead of stdout, in the rudy format unless
+    /// the extension is '.gr'
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
 }
@@ -0,0 +1,621 @@
+use std::{
+    collections::HashSet,
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{atomic::{AtomicUsize, Ordering}, mpsc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use max_cut::solver::Algorithm;
+
+use crate::args::{BatchArgs, Preprocessing};
+use crate::interrupt;
+use crate::output::{csv_field, json_string};
+
+const CSV_HEADER: &str =
+    "file,algorithm,status,vertices,edges,cut,exact,time_ms,best_known,gap_percent,record,preprocessing";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BatchFormat {
+    /// CSV with a header line
+    Csv,
+    /// One JSON object per run
+    Json,
+}
+
+/// How a run of an algorithm on an instance ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    /// Killed after the time limit
+    Timeout,
+    /// The algorithm failed or doesn't apply to the instance
+    Error,
+}
+
+impl Status {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Timeout => "timeout",
+            Status::Error => "error",
+        }
+    }
+}
+
+/// Values reported by a successful run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Values {
+    pub vertices: usize,
+    pub edges: usize,
+    pub cut: usize,
+    pub exact: bool,
+    pub time_ms: f64,
+    /// Best known value and gap to it if the instance is in the reference file
+    pub best_known: Option<(usize, f64)>,
+    pub record: bool,
+}
+
+impl Values {
+    fn best_known(&self) -> (String, String) {
+        match self.best_known {
+            Some((value, gap)) => (value.to_string(), format!("{gap:.3}")),
+            None => (String::new(), String::new()),
+        }
+    }
+}
+
+/// One line of the batch results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub file: String,
+    pub algorithm: String,
+    pub status: Status,
+    /// `None` unless `status` is [`Status::Ok`]
+    pub values: Option<Values>,
+    /// Preprocessing steps of the run, see [`Preprocessing::name`]
+    pub preprocessing: String,
+}
+
+impl Record {
+    fn to_csv(&self) -> String {
+        let values = match &self.values {
+            Some(values) => {
+                let (best_known, gap) = values.best_known();
+                format!(
+                    "{},{},{},{},{:.3},{best_known},{gap},{}",
+                    values.vertices, values.edges, values.cut, values.exact, values.time_ms, values.record
+                )
+            }
+            None => String::from(",,,,,,,"),
+        };
+
+        format!(
+            "{},{},{},{values},{}",
+            csv_field(&self.file),
+            csv_field(&self.algorithm),
+            self.status.as_str(),
+            csv_field(&self.preprocessing)
+        )
+    }
+
+    fn to_json(&self) -> String {
+        let values = match &self.values {
+            Some(values) => {
+                let (best_known, gap) = match values.best_known {
+                    Some(_) => values.best_known(),
+                    None => (String::from("null"), String::from("null")),
+                };
+                format!(
+                    "\"vertices\":{},\"edges\":{},\"cut\":{},\"exact\":{},\"time_ms\":{:.3},\
+                     \"best_known\":{best_known},\"gap_percent\":{gap},\"record\":{}",
+                    values.vertices, values.edges, values.cut, values.exact, values.time_ms, values.record
+                )
+            }
+            None => String::from(
+                "\"vertices\":null,\"edges\":null,\"cut\":null,\"exact\":null,\"time_ms\":null,\
+                 \"best_known\":null,\"gap_percent\":null,\"record\":null",
+            ),
+        };
+
+        format!(
+            "{{\"file\":{},\"algorithm\":{},\"status\":\"{}\",{values},\"preprocessing\":{}}}",
+            json_string(&self.file),
+            json_string(&self.algorithm),
+            self.status.as_str(),
+            json_string(&self.preprocessing)
+        )
+    }
+}
+
+/// Runs every algorithm of `algorithms` on every instance of `args.inputs`
+/// that isn't recorded in `args.output` yet and appends the results to it.
+///
+/// Every run is a child process executing `solve`, so a run exceeding
+/// `args.time_limit` can be killed without affecting the others.
+/// After SIGINT or SIGTERM no further runs are started and the running ones
+/// are stopped without being recorded, so they are repeated on resumption.
+pub fn batch(args: &BatchArgs, algorithms: &[Algorithm]) -> io::Result<()> {
+    interrupt::install();
+
+    let recorded = recorded(&args.output, args.output_format)?;
+    let preprocessing = args.preprocessing.name();
+    let mut runs: Vec<(PathBuf, Algorithm)> = instances(&args.inputs)?
+        .into_iter()
+        .flat_map(|file| algorithms.iter().map(move |algorithm| (file.clone(), *algorithm)))
+        .collect();
+
+    let all = runs.len();
+    runs.retain(|(file, algorithm)| {
+        !recorded.contains(&(file.to_str().unwrap().to_owned(), algorithm.to_string(), preprocessing.clone()))
+    });
+
+    let total = runs.len();
+    eprintln!("{} runs, {} already recorded", total, all - total);
+
+    let mut writer = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&args.output)?;
+
+    if args.output_format == BatchFormat::Csv && writer.metadata()?.len() == 0 {
+        writeln!(writer, "{CSV_HEADER}")?;
+    }
+
+    let jobs = args
+        .jobs
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |jobs| jobs.get()))
+        .max(1);
+    let time_limit = args.time_limit.map(Duration::from_secs);
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(total) {
+            let (sender, next, runs) = (sender.clone(), &next, &runs);
+
+            scope.spawn(move || {
+                while let Some((file, algorithm)) = runs
+                    .get(next.fetch_add(1, Ordering::Relaxed))
+                    .filter(|_| !interrupt::interrupted())
+                {
+                    let record = execute(file, *algorithm, args, time_limit);
+
+                    if sender.send(record).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        drop(sender);
+
+        for (done, record) in receiver.into_iter().enumerate() {
+            let Some(record) = record? else {
+                continue;
+            };
+            let new_record = if record.values.as_ref().is_some_and(|values| values.record) {
+                ", new record!"
+            } else {
+                ""
+            };
+            eprintln!(
+                "[{}/{total}] {} {}: {}{new_record}",
+                done + 1,
+                record.file,
+                record.algorithm,
+                record.status.as_str()
+            );
+
+            match args.output_format {
+                BatchFormat::Csv => writeln!(writer, "{}", record.to_csv())?,
+                BatchFormat::Json => writeln!(writer, "{}", record.to_json())?,
+            }
+            writer.flush()?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Runs `algorithm` on `file` in a child process.
+/// Returns `None` if the run was interrupted.
+fn execute(
+    file: &Path,
+    algorithm: Algorithm,
+    args: &BatchArgs,
+    time_limit: Option<Duration>,
+) -> io::Result<Option<Record>> {
+    let mut child = solve_command(file, algorithm, args)?.spawn()?;
+    let status = wait(&mut child, time_limit)?;
+
+    let mut record = Record {
+        file: file.to_str().unwrap().to_owned(),
+        algorithm: algorithm.to_string(),
+        status: Status::Error,
+        values: None,
+        preprocessing: args.preprocessing.name(),
+    };
+
+    match status {
+        Some(status) if status.code() == Some(interrupt::EXIT_INTERRUPTED) => return Ok(None),
+        None => record.status = Status::Timeout,
+        Some(status) if status.success() => {
+            let output = child.wait_with_output()?;
+            record.values = parse_solve_output(&String::from_utf8_lossy(&output.stdout));
+            if record.values.is_some() {
+                record.status = Status::Ok;
+            }
+        }
+        Some(_) => {
+            let output = child.wait_with_output()?;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            eprintln!("{}", stderr.trim().lines().last().unwrap_or("failed"));
+        }
+    }
+
+    Ok(Some(record))
+}
+
+/// The `solve` invocation of the current executable for `algorithm` on `file`.
+fn solve_command(file: &Path, algorithm: Algorithm, args: &BatchArgs) -> io::Result<Command> {
+    let mut command = Command::new(env::current_exe()?);
+    command
+        .arg("solve")
+        .arg(file)
+        .args(["--output-format", "csv", "--algorithm", algorithm.name()]);
+
+    let parameters = match algorithm {
+        Algorithm::Ilp => args
+            .algorithms
+            .timeout
+            .map(|timeout| vec![("--timeout", timeout.to_string())])
+            .unwrap_or_default(),
+        Algorithm::GreedyOrdered { order, passes } => {
+            vec![("--order", order.to_string()), ("--passes", passes.to_string())]
+        }
+        Algorithm::Oct { max_exact } => vec![("--max-exact", max_exact.to_string())],
+        Algorithm::Treewidth { max_width } => vec![("--max-width", max_width.to_string())],
+        Algorithm::BruteForce { max_size } => vec![("--max-size", max_size.to_string())],
+        _ => vec![],
+    };
+
+    for (name, value) in parameters {
+        command.arg(name).arg(value);
+    }
+
+    if let Some(best_known) = &args.best_known {
+        command.arg("--best-known").arg(best_known);
+    }
+
+    let Preprocessing {
+        decompose,
+        bipartite,
+        reduce,
+    } = args.preprocessing;
+
+    for (flag, set) in [("--decompose", decompose), ("--bipartite", bipartite), ("--reduce", reduce)] {
+        if set {
+            command.arg(flag);
+        }
+    }
+
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    Ok(command)
+}
+
+/// Waits for `child` to exit, kills it after `time_limit`.
+/// Passes an interruption of the batch on to `child`.
+/// Returns `None` if it was killed.
+fn wait(child: &mut Child, time_limit: Option<Duration>) -> io::Result<Option<ExitStatus>> {
+    let start = Instant::now();
+    let mut forwarded = false;
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+
+        if interrupt::interrupted() && !std::mem::replace(&mut forwarded, true) {
+            terminate(child)?;
+        }
+
+        if time_limit.is_some_and(|time_limit| start.elapsed() >= time_limit) {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Asks `child` to stop with SIGTERM, it exits once its cut is written.
+#[cfg(unix)]
+fn terminate(child: &mut Child) -> io::Result<()> {
+    // SAFETY: `kill` has no memory safety requirements
+    if unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGTERM) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(unix))]
+fn terminate(child: &mut Child) -> io::Result<()> {
+    child.kill()
+}
+
+/// Parses the CSV written by `solve --output-format csv`.
+fn parse_solve_output(output: &str) -> Option<Values> {
+    let line = output.lines().nth(1)?;
+    let mut fields = line.rsplitn(9, ',');
+
+    let record = fields.next()?.parse().ok()?;
+    let gap = fields.next()?;
+    let best_known = fields.next()?;
+    let best_known = if best_known.is_empty() {
+        None
+    } else {
+        Some((best_known.parse().ok()?, gap.parse().ok()?))
+    };
+    let time_ms = fields.next()?.parse().ok()?;
+    let exact = fields.next()?.parse().ok()?;
+    let cut = fields.next()?.parse().ok()?;
+    let edges = fields.next()?.parse().ok()?;
+    let vertices = fields.next()?.parse().ok()?;
+
+    Some(Values {
+        vertices,
+        edges,
+        cut,
+        exact,
+        time_ms,
+        best_known,
+        record,
+    })
+}
+
+/// The files given by `inputs`. A directory stands for all files inside it,
+/// `*` and `?` in the last component of a path match any characters.
+pub fn instances(inputs: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut instances = Vec::new();
+
+    for input in inputs {
+        let name = input.file_name().and_then(|name| name.to_str()).unwrap_or("");
+
+        if input.is_dir() {
+            let mut files = files(input, |_| true)?;
+            files.sort();
+            instances.append(&mut files);
+        } else if name.contains(['*', '?']) {
+            let parent = input.parent().unwrap_or(Path::new(""));
+            let directory = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+
+            let mut files: Vec<PathBuf> = files(directory, |file| {
+                file.to_str().is_some_and(|file| wildcard_match(name, file))
+            })?
+            .into_iter()
+            .map(|file| parent.join(file.file_name().unwrap()))
+            .collect();
+            files.sort();
+
+            if files.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no instance matches '{}'", input.to_str().unwrap()),
+                ));
+            }
+
+            instances.append(&mut files);
+        } else {
+            instances.push(input.clone());
+        }
+    }
+
+    Ok(instances)
+}
+
+/// The regular files in `directory` whose name satisfies `filter`.
+fn files(directory: &Path, filter: impl Fn(&std::ffi::OsStr) -> bool) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+
+        if entry.file_type()?.is_file() && filter(&entry.file_name()) {
+            files.push(entry.path());
+        }
+    }
+
+    Ok(files)
+}
+
+/// Whether `text` matches `pattern`, where `*` matches any characters and `?` a single one.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    // matches[j]: whether the pattern so far matches the first j characters
+    let mut matches = vec![false; text.len() + 1];
+    matches[0] = true;
+
+    for p in pattern {
+        let previous = matches.clone();
+
+        matches[0] = previous[0] && p == '*';
+        for j in 1..=text.len() {
+            matches[j] = match p {
+                '*' => previous[j] || matches[j - 1],
+                '?' => previous[j - 1],
+                c => previous[j - 1] && text[j - 1] == c,
+            };
+        }
+    }
+
+    matches[text.len()]
+}
+
+/// The pairs of file and algorithm already in the results file `path`.
+/// Runs are told apart by file, algorithm and preprocessing, which is empty
+/// in results written before it was recorded.
+fn recorded(path: &Path, format: BatchFormat) -> io::Result<HashSet<(String, String, String)>> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(HashSet::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut recorded = HashSet::new();
+
+    for line in raw.lines().filter(|line| !line.is_empty() && *line != CSV_HEADER) {
+        let run = match format {
+            BatchFormat::Csv => {
+                let fields = csv_fields(line);
+                let preprocessing = match fields.len() == CSV_HEADER.split(',').count() {
+                    true => fields.last().cloned(),
+                    false => Some(String::new()),
+                };
+                let mut fields = fields.into_iter();
+                fields.next().zip(fields.next()).zip(preprocessing)
+            }
+            BatchFormat::Json => json_field(line, "file")
+                .zip(json_field(line, "algorithm"))
+                .zip(json_field(line, "preprocessing").or(Some(String::new()))),
+        };
+
+        match run {
+            Some(((file, algorithm), preprocessing)) => recorded.insert((file, algorithm, preprocessing)),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("'{}' is not a batch result: {line}", path.to_str().unwrap()),
+                ))
+            }
+        };
+    }
+
+    Ok(recorded)
+}
+
+/// Splits a CSV line written with [`csv_field`].
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+
+    fields
+}
+
+/// The string value of `key` in a JSON object written on one line by [`json_string`].
+fn json_field(line: &str, key: &str) -> Option<String> {
+    let start = line.find(&format!("\"{key}\":\""))? + key.len() + 4;
+    let mut value = String::new();
+    let mut chars = line[start..].chars();
+
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                'u' => {
+                    let code: String = chars.by_ref().take(4).collect();
+                    value.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+                }
+                c => value.push(c),
+            },
+            c => value.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_match("g05_*", "g05_100.0"));
+        assert!(wildcard_match("*.gr", "vc-exact_001.gr"));
+        assert!(wildcard_match("vc-exact_00?.gr", "vc-exact_001.gr"));
+        assert!(!wildcard_match("*.gr", "g05_100.0"));
+        assert!(!wildcard_match("g05_?", "g05_100.0"));
+        assert!(wildcard_match("*", ""));
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let record = Record {
+            file: String::from("data/a, \"b\".gr"),
+            algorithm: String::from("oct(12)"),
+            status: Status::Timeout,
+            values: None,
+            preprocessing: String::from("decompose+reduce"),
+        };
+
+        let csv = csv_fields(&record.to_csv());
+        assert_eq!(csv[0], record.file);
+        assert_eq!(csv[1], record.algorithm);
+        assert_eq!(csv.len(), CSV_HEADER.split(',').count());
+        assert_eq!(csv[11], record.preprocessing);
+
+        let json = record.to_json();
+        assert_eq!(json_field(&json, "file").unwrap(), record.file);
+        assert_eq!(json_field(&json, "algorithm").unwrap(), record.algorithm);
+        assert_eq!(json_field(&json, "status").unwrap(), "timeout");
+        assert_eq!(json_field(&json, "preprocessing").unwrap(), record.preprocessing);
+    }
+
+    #[test]
+    fn solve_output() {
+        let values = parse_solve_output(
+            "file,algorithm,vertices,edges,cut,exact,time_ms,best_known,gap_percent,record\n\
+             \"a,b\",greedy,5,7,6,false,0.250,,,false\n",
+        );
+
+        assert_eq!(
+            values,
+            Some(Values {
+                vertices: 5,
+                edges: 7,
+                cut: 6,
+                exact: false,
+                time_ms: 0.25,
+                best_known: None,
+                record: false,
+            })
+        );
+
+        let values = parse_solve_output(
+            "file,algorithm,vertices,edges,cut,exact,time_ms,best_known,gap_percent,record\n\
+             a,greedy,5,7,6,false,0.250,5,-20.000,true\n",
+        )
+        .unwrap();
+
+        assert_eq!(values.best_known, Some((5, -20.0)));
+        assert!(values.record);
+    }
+}
@@ -0,0 +1,215 @@
+//! Best known cut values of instances, to report the gap of a cut to them.
+//!
+//! A reference file has one instance per line, fields are separated by commas
+//! or whitespace and lines starting with `#` are comments:
+//!
+//! ```text
+//! # instance          value  optimal
+//! G1                  11624
+//! g05_60.0            535    optimal
+//! 3f1c0a9e6b2d4c87    48418  optimal
+//! ```
+//!
+//! An instance is either a file name, matched against the file name of the
+//! graph without its directory, or the 16 hex digits of
+//! [`certificate::instance_hash`]. The result files of the old bench scripts
+//! (`graph, vertices, edges, cut-size, time in ms`) and of `batch` are read as
+//! well, runs without a value like timeouts are skipped. If an instance occurs
+//! more than once its largest value counts.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::certificate;
+use crate::graph::Graph;
+
+/// Best known cut value of an instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BestKnown {
+    pub value: usize,
+    /// Whether `value` is known to be the maximum cut
+    pub optimal: bool,
+}
+
+impl BestKnown {
+    /// Relative distance of `value` below the best known value,
+    /// negative if `value` is a new record.
+    pub fn gap(&self, value: usize) -> f64 {
+        if self.value == 0 {
+            0.0
+        } else {
+            (self.value as f64 - value as f64) / self.value as f64
+        }
+    }
+
+    /// Whether `value` beats the best known value.
+    /// For an optimal value this means the cut or the reference is wrong.
+    pub fn is_record(&self, value: usize) -> bool {
+        value > self.value
+    }
+
+    fn merge(&mut self, other: BestKnown) {
+        if other.value > self.value {
+            *self = other;
+        } else if other.value == self.value {
+            self.optimal |= other.optimal;
+        }
+    }
+}
+
+/// Best known values keyed by file name or instance hash.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Registry {
+    by_name: HashMap<String, BestKnown>,
+    by_hash: HashMap<u64, BestKnown>,
+}
+
+impl Registry {
+    /// Parses a reference file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first malformed line.
+    pub fn parse(raw: &str) -> Result<Registry, String> {
+        let mut registry = Registry::default();
+        let mut first = true;
+
+        for line in raw.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = if line.contains(',') {
+                line.split(',').map(str::trim).collect()
+            } else {
+                line.split_whitespace().collect()
+            };
+            let unrecognized = || format!("unrecognized line: \'{line}\'");
+
+            // Value and optimality of the formats told apart by the number of fields
+            let (value, optimal) = match fields[..] {
+                [_, value] => (value, false),
+                [_, value, optimal] => (value, matches!(optimal, "optimal" | "true")),
+                // graph, vertices, edges, cut-size, time in ms
+                [_, _, _, value, _] => (value, false),
+                // file, algorithm, status, vertices, edges, cut, exact, ...
+                [_, _, _, _, _, value, exact, ..] => (value, exact == "true"),
+                _ => return Err(unrecognized()),
+            };
+
+            let header = std::mem::replace(&mut first, false);
+
+            let value = match value.parse() {
+                Ok(value) => value,
+                // Runs that ended without a cut
+                Err(_) if value.is_empty() || value == "timeout" => continue,
+                Err(_) if header => continue,
+                Err(_) => return Err(unrecognized()),
+            };
+
+            registry.insert(fields[0], BestKnown { value, optimal });
+        }
+
+        Ok(registry)
+    }
+
+    /// Adds `best_known` for the instance `key`, a file name or instance hash.
+    /// Keeps the larger value if `key` is already known.
+    pub fn insert(&mut self, key: &str, best_known: BestKnown) {
+        let known = match parse_hash(key) {
+            Some(hash) => self.by_hash.entry(hash).or_insert(best_known),
+            None => self
+                .by_name
+                .entry(file_name(key).to_owned())
+                .or_insert(best_known),
+        };
+
+        known.merge(best_known);
+    }
+
+    /// Best known value of `graph` read from `file`, by the instance hash
+    /// if it is known, else by the file name.
+    pub fn lookup(&self, file: &Path, graph: &Graph) -> Option<BestKnown> {
+        self.by_hash
+            .get(&certificate::instance_hash(graph))
+            .or_else(|| self.by_name.get(file_name(file.to_str()?)))
+            .copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_name.len() + self.by_hash.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn parse_hash(key: &str) -> Option<u64> {
+    if key.len() == 16 {
+        u64::from_str_radix(key, 16).ok()
+    } else {
+        None
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats() -> Result<(), String> {
+        let mut graph = Graph::new_empty(3);
+        graph.add_edges(&[(0, 1), (1, 2), (0, 2)]);
+        let hash = format!("{:016x}", certificate::instance_hash(&graph));
+
+        let registry = Registry::parse(&format!(
+            "# reference values\n\
+             G1 11624\n\
+             g05_60.0, 535, optimal\n\
+             {hash} 2 optimal\n\
+             benches/data/rudy/g05_60.0,greedy,ok,60,885,467,false,1.627\n"
+        ))?;
+        let bench = Registry::parse(
+            "graph, vertices, edges, cut-size, time in ms\n\
+             data/vc_exact/vc-exact_001.gr, timeout\n\
+             data/vc_exact/vc-exact_003.gr, 60541, 48418, 48418, 5640\n",
+        )?;
+
+        let lookup = |file: &str| registry.lookup(Path::new(file), &Graph::new_empty(1));
+
+        assert_eq!(registry.len(), 3);
+        assert_eq!(lookup("gset/G1"), Some(BestKnown { value: 11624, optimal: false }));
+        assert_eq!(lookup("benches/data/rudy/g05_60.0"), Some(BestKnown { value: 535, optimal: true }));
+        assert_eq!(bench.len(), 1);
+        assert_eq!(
+            bench.lookup(Path::new("vc-exact_003.gr"), &graph).map(|known| known.value),
+            Some(48418)
+        );
+        assert_eq!(
+            registry.lookup(Path::new("triangle.gr"), &graph),
+            Some(BestKnown { value: 2, optimal: true })
+        );
+
+        assert!(Registry::parse("G1 11624\nG2 many").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn gap_and_records() {
+        let known = BestKnown {
+            value: 200,
+            optimal: false,
+        };
+
+        assert_eq!(known.gap(150), 0.25);
+        assert!(known.gap(210) < 0.0);
+        assert!(known.is_record(201));
+        assert!(!known.is_record(200));
+    }
+}
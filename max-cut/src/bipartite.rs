@@ -0,0 +1,774 @@
+//! Max-Cut on bipartite and nearly bipartite graphs.
+//!
+//! A bipartite graph has all its edges in the maximum cut. If removing a small
+//! odd cycle transversal `X` makes a graph bipartite, a maximum cut is found by
+//! trying all sides for `X`. For every placement of `X` the best placement of
+//! the bipartite rest is a minimum cut in a flow network. Larger transversals
+//! are placed by moving single vertices, where every move only updates the
+//! flow of the placement before.
+
+use crate::control::RunControl;
+use crate::decompose;
+use crate::graph::{Edge, Graph};
+use crate::parallel;
+
+/// Largest transversal for which [`near_bipartite`] tries all placements,
+/// larger values of `max_exact` are lowered to it.
+pub const EXACT_LIMIT: usize = 20;
+
+/// Colors every component by breadth first search.
+/// The coloring is proper exactly on the bipartite components.
+pub fn two_coloring(graph: &Graph) -> Vec<bool> {
+    coloring_without(graph, &vec![false; graph.size()])
+}
+
+pub fn is_bipartite(graph: &Graph) -> bool {
+    let colors = two_coloring(graph);
+    graph.all_edges().iter().all(|edge| colors[edge.0] != colors[edge.1])
+}
+
+/// Returns every edge if `graph` is bipartite, which is its maximum cut.
+pub fn bipartite_cut(graph: &Graph) -> Option<Vec<Edge>> {
+    let colors = two_coloring(graph);
+    let edges = graph.all_edges();
+
+    if edges.iter().all(|edge| colors[edge.0] != colors[edge.1]) {
+        Some(edges)
+    } else {
+        None
+    }
+}
+
+/// Cuts every bipartite component of `graph` completely and solves the
+/// remaining components with `solver`.
+///
+/// # Errors
+///
+/// Returns the first error of `solver`.
+pub fn solve<F, E>(graph: &Graph, solver: F) -> Result<Vec<Edge>, E>
+where
+    F: Fn(&Graph) -> Result<Vec<Edge>, E> + Sync,
+    E: Send,
+{
+    decompose::solve_components(graph, |component| match bipartite_cut(component) {
+        Some(cut) => Ok(cut),
+        None => solver(component),
+    })
+}
+
+/// Greedily finds a set of vertices whose removal makes `graph` bipartite.
+/// Repeatedly removes the vertex with the most edges inside a color class
+/// of a breadth first coloring of the remaining graph.
+pub fn odd_cycle_transversal(graph: &Graph) -> Vec<usize> {
+    let mut removed = vec![false; graph.size()];
+    let mut transversal = vec![];
+
+    loop {
+        let colors = coloring_without(graph, &removed);
+        let mut conflicts = vec![0usize; graph.size()];
+
+        for edge in graph.all_edges() {
+            if !removed[edge.0] && !removed[edge.1] && colors[edge.0] == colors[edge.1] {
+                conflicts[edge.0] += 1;
+                conflicts[edge.1] += 1;
+            }
+        }
+
+        let (vertex, count) = conflicts
+            .iter()
+            .enumerate()
+            .max_by_key(|(vertex, count)| (**count, usize::MAX - vertex))
+            .unwrap_or((0, &0));
+
+        if *count == 0 {
+            break;
+        }
+
+        removed[vertex] = true;
+        transversal.push(vertex);
+    }
+
+    transversal
+}
+
+/// A cut found with the help of an odd cycle transversal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransversalCut {
+    pub cut: Vec<Edge>,
+    pub transversal: Vec<usize>,
+    /// Whether all sides of the transversal were tried, making `cut` maximum
+    pub exact: bool,
+}
+
+/// Finds a cut of a nearly bipartite `graph`. If the transversal found by
+/// [`odd_cycle_transversal`] has at most `max_exact` vertices, but at most
+/// [`EXACT_LIMIT`], all `2^(|X| - 1)` placements are tried in parallel and the cut is maximum.
+/// Otherwise the placement is improved by moving single transversal vertices.
+pub fn near_bipartite(graph: &Graph, max_exact: usize) -> TransversalCut {
+    near_bipartite_controlled(graph, max_exact, &RunControl::new())
+}
+
+/// [`near_bipartite`] that stops early when `control` asks to and then returns
+/// the best cut found so far, which is not exact. Every evaluated placement of
+/// the transversal is one iteration.
+pub fn near_bipartite_controlled(graph: &Graph, max_exact: usize, control: &RunControl) -> TransversalCut {
+    let transversal = odd_cycle_transversal(graph);
+    let instance = Transversal::new(graph, &transversal);
+    let (placement, exact) = if transversal.len() <= max_exact.min(EXACT_LIMIT) {
+        instance.best_placement(control)
+    } else {
+        (instance.local_placement(control), false)
+    };
+
+    let cut = graph.cut(&instance.sides(&placement));
+    control.improve(&cut);
+
+    TransversalCut {
+        cut,
+        transversal,
+        exact,
+    }
+}
+
+fn coloring_without(graph: &Graph, removed: &[bool]) -> Vec<bool> {
+    let mut colors = vec![false; graph.size()];
+    let mut visited = removed.to_vec();
+
+    for start in 0..graph.size() {
+        if visited[start] {
+            continue;
+        }
+
+        visited[start] = true;
+        let mut queue = vec![start];
+        let mut i = 0;
+
+        while i < queue.len() {
+            let vertex = queue[i];
+            i += 1;
+
+            for neigh in graph.neighbors(vertex) {
+                if !visited[*neigh] {
+                    visited[*neigh] = true;
+                    colors[*neigh] = !colors[vertex];
+                    queue.push(*neigh);
+                }
+            }
+        }
+    }
+
+    colors
+}
+
+/// `graph` split into a transversal `X` and the bipartite rest `B`.
+///
+/// Once `X` is placed, let `y_v` tell whether `v` of `B` is put on the side
+/// opposite of its color. An edge inside of `B` is cut iff its ends have the
+/// same `y`, an edge into `X` is cut depending on `y_v` only. Minimizing the
+/// uncut edges is thus a minimum s-t cut problem with unit capacities between
+/// the vertices of `B`.
+struct Transversal<'a> {
+    transversal: &'a [usize],
+    colors: Vec<bool>,
+    /// Vertices of `B` in components adjacent to `X`,
+    /// vertex `vertices[i]` is node `i + 2` of the network
+    vertices: Vec<usize>,
+    network: Network,
+    /// For every vertex in `vertices` its neighbors in `X` as indices into `X`
+    /// and the arcs from the source and to the sink
+    links: Vec<(Vec<usize>, usize, usize)>,
+    /// Edges inside of `X` as pairs of indices
+    inner: Vec<(usize, usize)>,
+}
+
+impl<'a> Transversal<'a> {
+    fn new(graph: &'a Graph, transversal: &'a [usize]) -> Transversal<'a> {
+        let mut index = vec![usize::MAX; graph.size()];
+        for (i, vertex) in transversal.iter().enumerate() {
+            index[*vertex] = i;
+        }
+
+        let removed: Vec<bool> = index.iter().map(|i| *i != usize::MAX).collect();
+        let colors = coloring_without(graph, &removed);
+
+        // Collect the components of B adjacent to X
+        let mut node = vec![usize::MAX; graph.size()];
+        let mut vertices = vec![];
+
+        for start in transversal.iter().flat_map(|x| graph.neighbors(*x)) {
+            if removed[*start] || node[*start] != usize::MAX {
+                continue;
+            }
+
+            node[*start] = vertices.len() + 2;
+            vertices.push(*start);
+            let mut i = vertices.len() - 1;
+
+            while i < vertices.len() {
+                let vertex = vertices[i];
+                i += 1;
+
+                for neigh in graph.neighbors(vertex) {
+                    if !removed[*neigh] && node[*neigh] == usize::MAX {
+                        node[*neigh] = vertices.len() + 2;
+                        vertices.push(*neigh);
+                    }
+                }
+            }
+        }
+
+        let mut network = Network::new(vertices.len() + 2);
+        let mut links = vec![];
+
+        for vertex in &vertices {
+            let mut neighbors_in_x = vec![];
+
+            for neigh in graph.neighbors(*vertex) {
+                if removed[*neigh] {
+                    neighbors_in_x.push(index[*neigh]);
+                } else if *neigh > *vertex {
+                    network.add_edge(node[*vertex], node[*neigh], 1);
+                }
+            }
+
+            let source = network.add_arc(0, node[*vertex]);
+            let sink = network.add_arc(node[*vertex], 1);
+            links.push((neighbors_in_x, source, sink));
+        }
+
+        let mut inner = vec![];
+        for (i, vertex) in transversal.iter().enumerate() {
+            for neigh in graph.neighbors(*vertex) {
+                if removed[*neigh] && index[*neigh] > i {
+                    inner.push((i, index[*neigh]));
+                }
+            }
+        }
+
+        Transversal {
+            transversal,
+            colors,
+            vertices,
+            network,
+            links,
+            inner,
+        }
+    }
+
+    /// Returns the number of cut edges not inside of `B` plus the number of
+    /// edges of `B` minus the uncut edges of `B`, if `X` is placed by
+    /// `placement` and `B` optimally. Also returns `y` for [`Self::vertices`].
+    fn evaluate(&self, placement: &[bool]) -> (usize, Vec<bool>) {
+        let mut capacity = self.network.capacity.clone();
+        let mut value = self
+            .inner
+            .iter()
+            .filter(|(a, b)| placement[*a] != placement[*b])
+            .count();
+
+        for ((neighbors_in_x, source, sink), vertex) in self.links.iter().zip(&self.vertices) {
+            // Edges into X left uncut if the vertex keeps its color, or not
+            let kept = neighbors_in_x
+                .iter()
+                .filter(|x| placement[**x] == self.colors[*vertex])
+                .count();
+            let flipped = neighbors_in_x.len() - kept;
+
+            value += neighbors_in_x.len() - kept.min(flipped);
+            // Being on the sink side means flipping
+            capacity[*source] = flipped - kept.min(flipped);
+            capacity[*sink] = kept - kept.min(flipped);
+        }
+
+        let flow = self.network.max_flow(&mut capacity);
+        let reachable = self.network.reachable(&capacity);
+
+        (value - flow, reachable[2..].iter().map(|source| !source).collect())
+    }
+
+    /// Tries all placements of `X` with the first vertex on side `false`.
+    /// Also returns whether all of them were tried before `control` stopped.
+    fn best_placement(&self, control: &RunControl) -> (Vec<bool>, bool) {
+        let k = self.transversal.len();
+        let free = k.saturating_sub(1);
+        // Fix the low bits of the placement per task
+        let task_bits = free.min(8);
+        let tasks: Vec<u64> = (0..1u64 << task_bits).collect();
+
+        let results = parallel::map(&tasks, |task| {
+            let mut placement = vec![false; k];
+            let mut best: Option<(usize, u64)> = None;
+            let mut complete = true;
+
+            for high in 0..1u64 << (free - task_bits) {
+                if !control.proceed() {
+                    complete = false;
+                    break;
+                }
+
+                let mask = (high << task_bits) | task;
+                for (i, side) in placement.iter_mut().enumerate().skip(1) {
+                    *side = mask & (1 << (i - 1)) != 0;
+                }
+
+                let (value, _) = self.evaluate(&placement);
+                if best.is_none_or(|(best, _)| value > best) {
+                    best = Some((value, mask));
+                }
+            }
+
+            (best, complete)
+        });
+
+        let complete = results.iter().all(|(_, complete)| *complete);
+        let best = results
+            .into_iter()
+            .filter_map(|(best, _)| best)
+            .max_by_key(|(value, mask)| (*value, u64::MAX - mask))
+            .map_or(0, |(_, mask)| mask);
+
+        ((0..k).map(|i| i > 0 && best & (1 << (i - 1)) != 0).collect(), complete)
+    }
+
+    /// Moves single vertices of `X` to the other side while that improves the cut.
+    fn local_placement(&self, control: &RunControl) -> Vec<bool> {
+        let mut state = Placement::new(self);
+        let mut value = state.value();
+        let mut improved = true;
+
+        while improved {
+            improved = false;
+
+            for i in 0..self.transversal.len() {
+                if !control.proceed() {
+                    return state.placement;
+                }
+
+                state.flip(i);
+
+                if state.value() > value {
+                    value = state.value();
+                    improved = true;
+                } else {
+                    state.flip(i);
+                }
+            }
+        }
+
+        state.placement
+    }
+
+    /// Sides of all vertices for a placement of `X`.
+    fn sides(&self, placement: &[bool]) -> Vec<bool> {
+        let mut sides = self.colors.clone();
+
+        for (vertex, flip) in self.vertices.iter().zip(self.evaluate(placement).1) {
+            sides[*vertex] ^= flip;
+        }
+
+        for (vertex, side) in self.transversal.iter().zip(placement) {
+            sides[*vertex] = *side;
+        }
+
+        sides
+    }
+}
+
+/// A placement of `X` together with a maximum flow of its network, see
+/// [`Transversal::evaluate`]. Moving a vertex of `X` only changes the arcs from
+/// the source and to the sink of its neighbors, so a move cancels the flow
+/// beyond their new capacities and augments from the remaining flow.
+struct Placement<'t, 'a> {
+    instance: &'t Transversal<'a>,
+    placement: Vec<bool>,
+    /// For every vertex in `vertices` its neighbors in `X` on the side of its color
+    kept: Vec<usize>,
+    /// For every vertex of `X` its neighbors in `vertices` and in `X` as indices
+    neighbors: Vec<(Vec<usize>, Vec<usize>)>,
+    /// Residual capacities of the network
+    capacity: Vec<usize>,
+    flow: usize,
+    /// Value of the placement before subtracting the flow
+    bound: usize,
+}
+
+impl<'t, 'a> Placement<'t, 'a> {
+    /// Places all of `X` on side `false`.
+    fn new(instance: &'t Transversal<'a>) -> Placement<'t, 'a> {
+        let mut neighbors = vec![(vec![], vec![]); instance.transversal.len()];
+        for (link, (neighbors_in_x, _, _)) in instance.links.iter().enumerate() {
+            neighbors_in_x.iter().for_each(|x| neighbors[*x].0.push(link));
+        }
+        for (a, b) in &instance.inner {
+            neighbors[*a].1.push(*b);
+            neighbors[*b].1.push(*a);
+        }
+
+        let kept = instance
+            .links
+            .iter()
+            .zip(&instance.vertices)
+            .map(|((neighbors_in_x, _, _), vertex)| if instance.colors[*vertex] { 0 } else { neighbors_in_x.len() })
+            .collect();
+
+        let mut state = Placement {
+            instance,
+            placement: vec![false; instance.transversal.len()],
+            kept,
+            neighbors,
+            capacity: instance.network.capacity.clone(),
+            flow: 0,
+            bound: 0,
+        };
+
+        for link in 0..instance.links.len() {
+            state.bound += state.link_bound(link);
+            state.update_capacities(link);
+        }
+        state.flow = instance.network.max_flow(&mut state.capacity);
+
+        state
+    }
+
+    /// Cut edges if `X` is placed by `placement` and `B` optimally.
+    fn value(&self) -> usize {
+        self.bound - self.flow
+    }
+
+    /// Moves vertex `i` of `X` to the other side.
+    fn flip(&mut self, i: usize) {
+        let instance = self.instance;
+        self.placement[i] = !self.placement[i];
+
+        for other in &self.neighbors[i].1 {
+            if self.placement[*other] != self.placement[i] {
+                self.bound += 1;
+            } else {
+                self.bound -= 1;
+            }
+        }
+
+        for j in 0..self.neighbors[i].0.len() {
+            let link = self.neighbors[i].0[j];
+            self.bound -= self.link_bound(link);
+
+            if self.placement[i] == instance.colors[instance.vertices[link]] {
+                self.kept[link] += 1;
+            } else {
+                self.kept[link] -= 1;
+            }
+
+            self.bound += self.link_bound(link);
+            self.update_capacities(link);
+        }
+
+        self.flow += instance.network.max_flow(&mut self.capacity);
+    }
+
+    /// Edges between `vertices[link]` and `X` that are cut at least.
+    fn link_bound(&self, link: usize) -> usize {
+        let total = self.instance.links[link].0.len();
+        total - self.kept[link].min(total - self.kept[link])
+    }
+
+    /// Sets the arcs of `vertices[link]` from the source and to the sink to
+    /// the capacities of [`Transversal::evaluate`].
+    fn update_capacities(&mut self, link: usize) {
+        let (neighbors_in_x, source, sink) = &self.instance.links[link];
+        let kept = self.kept[link];
+        let flipped = neighbors_in_x.len() - kept;
+
+        self.set_capacity(*source, flipped - kept.min(flipped), true);
+        self.set_capacity(*sink, kept - kept.min(flipped), false);
+    }
+
+    /// Sets the capacity of an arc from the source if `from_source`, otherwise
+    /// of an arc to the sink, and cancels the flow beyond it.
+    fn set_capacity(&mut self, arc: usize, capacity: usize, from_source: bool) {
+        let network = &self.instance.network;
+        // The reverse arc starts without capacity, so its residual capacity is the flow
+        let flow = self.capacity[arc ^ 1];
+        let excess = flow.saturating_sub(capacity);
+
+        for _ in 0..excess {
+            if from_source {
+                self.cancel(network.head[arc], true);
+            } else {
+                self.cancel(network.head[arc ^ 1], false);
+            }
+        }
+
+        self.capacity[arc ^ 1] = flow - excess;
+        self.capacity[arc] = capacity - (flow - excess);
+        self.flow -= excess;
+    }
+
+    /// Removes one unit of flow along a path from `start` to the sink if
+    /// `forward`, otherwise from the source to `start`. The arcs between
+    /// `start` and the source or sink it is not walking to stay unchanged.
+    fn cancel(&mut self, start: usize, forward: bool) {
+        let network = &self.instance.network;
+        let (target, avoided) = if forward { (1, 0) } else { (0, 1) };
+        // The arc carrying the flow of a walked arc, which goes backwards towards the source
+        let flow_arc = |arc: usize| if forward { arc } else { arc ^ 1 };
+
+        let mut parent = vec![usize::MAX; network.arcs.len()];
+        parent[start] = 0;
+        let mut stack = vec![start];
+
+        while let Some(node) = stack.pop() {
+            if node == target {
+                break;
+            }
+
+            for arc in &network.arcs[node] {
+                let next = network.head[*arc];
+                let reverse = flow_arc(*arc) ^ 1;
+
+                // Flow raises the residual capacity of the reverse arc above its capacity
+                if next != avoided
+                    && next != start
+                    && parent[next] == usize::MAX
+                    && self.capacity[reverse] > network.capacity[reverse]
+                {
+                    parent[next] = *arc;
+                    stack.push(next);
+                }
+            }
+        }
+
+        let mut node = target;
+        while node != start {
+            let arc = parent[node];
+            self.capacity[flow_arc(arc)] += 1;
+            self.capacity[flow_arc(arc) ^ 1] -= 1;
+            node = network.head[arc ^ 1];
+        }
+    }
+}
+
+/// A flow network with source `0` and sink `1`.
+/// Arc `a ^ 1` is the reverse of arc `a`.
+struct Network {
+    arcs: Vec<Vec<usize>>,
+    head: Vec<usize>,
+    capacity: Vec<usize>,
+}
+
+impl Network {
+    fn new(nodes: usize) -> Network {
+        Network {
+            arcs: vec![vec![]; nodes],
+            head: vec![],
+            capacity: vec![],
+        }
+    }
+
+    /// Adds an arc without capacity and returns its id.
+    fn add_arc(&mut self, from: usize, to: usize) -> usize {
+        self.add_edge(from, to, 0);
+        self.head.len() - 2
+    }
+
+    /// Adds an undirected edge with `capacity` in both directions.
+    fn add_edge(&mut self, a: usize, b: usize, capacity: usize) {
+        self.arcs[a].push(self.head.len());
+        self.head.push(b);
+        self.capacity.push(capacity);
+        self.arcs[b].push(self.head.len());
+        self.head.push(a);
+        self.capacity.push(capacity);
+    }
+
+    /// Augments along shortest paths until the sink can't be reached.
+    fn max_flow(&self, capacity: &mut [usize]) -> usize {
+        let mut flow = 0;
+        let mut parent = vec![usize::MAX; self.arcs.len()];
+
+        loop {
+            parent.iter_mut().for_each(|arc| *arc = usize::MAX);
+            let mut queue = vec![0];
+            let mut i = 0;
+
+            while i < queue.len() && parent[1] == usize::MAX {
+                let node = queue[i];
+                i += 1;
+
+                for arc in &self.arcs[node] {
+                    let next = self.head[*arc];
+                    if capacity[*arc] > 0 && next != 0 && parent[next] == usize::MAX {
+                        parent[next] = *arc;
+                        queue.push(next);
+                    }
+                }
+            }
+
+            if parent[1] == usize::MAX {
+                return flow;
+            }
+
+            let mut bottleneck = usize::MAX;
+            let mut node = 1;
+            while node != 0 {
+                bottleneck = bottleneck.min(capacity[parent[node]]);
+                node = self.head[parent[node] ^ 1];
+            }
+
+            let mut node = 1;
+            while node != 0 {
+                capacity[parent[node]] -= bottleneck;
+                capacity[parent[node] ^ 1] += bottleneck;
+                node = self.head[parent[node] ^ 1];
+            }
+
+            flow += bottleneck;
+        }
+    }
+
+    /// Nodes reachable from the source in the residual network.
+    fn reachable(&self, capacity: &[usize]) -> Vec<bool> {
+        let mut reachable = vec![false; self.arcs.len()];
+        reachable[0] = true;
+        let mut stack = vec![0];
+
+        while let Some(node) = stack.pop() {
+            for arc in &self.arcs[node] {
+                let next = self.head[*arc];
+                if capacity[*arc] > 0 && !reachable[next] {
+                    reachable[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+
+        reachable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brute_force::{self, LIMIT};
+    use crate::generate;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    #[test]
+    fn even_cycle() {
+        let mut graph = Graph::new_empty(6);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)]);
+
+        assert!(is_bipartite(&graph));
+        assert_eq!(bipartite_cut(&graph), Some(graph.all_edges()));
+        assert!(odd_cycle_transversal(&graph).is_empty());
+    }
+
+    #[test]
+    fn odd_cycle() {
+        let mut graph = Graph::new_empty(5);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+
+        assert!(!is_bipartite(&graph));
+        assert_eq!(bipartite_cut(&graph), None);
+        assert_eq!(odd_cycle_transversal(&graph).len(), 1);
+
+        let result = near_bipartite(&graph, 4);
+        assert!(result.exact);
+        assert_eq!(result.cut.len(), 4);
+    }
+
+    #[test]
+    fn near_bipartite_is_exact() {
+        // A 4x4 grid with three diagonals added
+        let mut graph = Graph::new_empty(16);
+        for v in 0..16 {
+            if v % 4 < 3 {
+                graph.add_edge(&(v, v + 1));
+            }
+            if v < 12 {
+                graph.add_edge(&(v, v + 4));
+            }
+        }
+        graph.add_edges(&[(0, 5), (6, 11), (9, 14)]);
+
+        let result = near_bipartite(&graph, 10);
+
+        assert!(result.exact);
+        assert!(graph.partition(&result.cut).is_some());
+        let best = brute_force::max_cut_brute_force(&graph, LIMIT).unwrap();
+        assert_eq!(result.cut.len(), best.len());
+    }
+
+    #[test]
+    fn random_near_bipartite_is_exact() {
+        let mut rng = SmallRng::seed_from_u64(29);
+
+        for _ in 0..100 {
+            let n = rng.gen_range(2..13);
+            let mut graph = Graph::new_empty(n);
+
+            for u in 0..n {
+                for v in u + 1..n {
+                    // Mostly edges between even and odd vertices
+                    let p = if (u + v) % 2 == 1 { 0.5 } else { 0.1 };
+                    if rng.gen_bool(p) {
+                        graph.add_edge(&(u, v));
+                    }
+                }
+            }
+
+            let result = near_bipartite(&graph, n);
+
+            assert!(result.exact);
+            assert!(graph.partition(&result.cut).is_some());
+            let best = brute_force::max_cut_brute_force(&graph, LIMIT).unwrap();
+            assert_eq!(result.cut.len(), best.len());
+        }
+    }
+
+    #[test]
+    fn flips_keep_the_flow_maximum() {
+        let mut rng = SmallRng::seed_from_u64(290);
+
+        for _ in 0..50 {
+            let n = rng.gen_range(8..40);
+            let graph = generate::gnp(n, rng.gen_range(0.1..0.5), rng.gen());
+            let transversal = odd_cycle_transversal(&graph);
+            let instance = Transversal::new(&graph, &transversal);
+            let mut state = Placement::new(&instance);
+
+            for _ in 0..4 * transversal.len() {
+                state.flip(rng.gen_range(0..transversal.len()));
+                assert_eq!(state.value(), instance.evaluate(&state.placement).0);
+            }
+        }
+    }
+
+    #[test]
+    fn bipartite_components_are_skipped() {
+        // A triangle and a path
+        let mut graph = Graph::new_empty(6);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5)]);
+
+        let cut = solve(&graph, |component| {
+            assert_eq!(component.size(), 3);
+            Ok::<_, ()>(vec![Edge(0, 1), Edge(1, 2)])
+        })
+        .unwrap();
+
+        assert_eq!(cut.len(), 4);
+    }
+
+    #[test]
+    fn max_exact_is_capped() {
+        // Every triangle needs a vertex of its own in the transversal
+        let mut graph = Graph::new_empty(3 * (EXACT_LIMIT + 2));
+        for t in 0..EXACT_LIMIT + 2 {
+            graph.add_edges(&[(3 * t, 3 * t + 1), (3 * t + 1, 3 * t + 2), (3 * t + 2, 3 * t)]);
+        }
+
+        let result = near_bipartite(&graph, usize::MAX);
+        assert!(result.transversal.len() > EXACT_LIMIT);
+        assert!(!result.exact);
+        assert_eq!(result.cut.len(), 2 * (EXACT_LIMIT + 2));
+    }
+}
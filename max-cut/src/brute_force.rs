@@ -0,0 +1,195 @@
+//! Exhaustive Max-Cut for small graphs, independent of any solver backend.
+//!
+//! The last vertex stays on one side, the sides of the others are enumerated
+//! in Gray code order. Consecutive bipartitions differ in a single vertex, so
+//! the cut value is updated in `O(deg)` per step. The sides of the highest
+//! vertices form prefixes that are enumerated in parallel.
+
+use std::fmt;
+
+use crate::control::RunControl;
+use crate::graph::{Edge, Graph};
+use crate::parallel;
+
+/// Steps of a task between two checks of the [`RunControl`].
+const CHECK_INTERVAL: u64 = 1 << 16;
+
+/// Largest graph [`max_cut_brute_force`] can handle at all.
+pub const LIMIT: usize = 64;
+
+/// Error indicating that a graph has too many vertices for exhaustive search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooLargeError {
+    pub size: usize,
+    pub max_size: usize,
+}
+
+impl std::error::Error for TooLargeError {}
+
+impl fmt::Display for TooLargeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Graph has {} vertices, exhaustive search is limited to {}!",
+            self.size, self.max_size
+        )
+    }
+}
+
+/// Calculates a maximum cut of `graph` by trying all `2^(n - 1)` bipartitions.
+///
+/// # Errors
+///
+/// Returns [`TooLargeError`] if `graph` has more than `max_size` vertices
+/// or more than [`LIMIT`].
+pub fn max_cut_brute_force(graph: &Graph, max_size: usize) -> Result<Vec<Edge>, TooLargeError> {
+    max_cut_brute_force_controlled(graph, max_size, &RunControl::new())
+}
+
+/// [`max_cut_brute_force`] that stops early when `control` asks to and then
+/// returns the best cut found so far. Every enumerated prefix of the highest
+/// vertices is one iteration.
+///
+/// # Errors
+///
+/// Returns [`TooLargeError`] if `graph` has more than `max_size` vertices
+/// or more than [`LIMIT`].
+pub fn max_cut_brute_force_controlled(
+    graph: &Graph,
+    max_size: usize,
+    control: &RunControl,
+) -> Result<Vec<Edge>, TooLargeError> {
+    let n = graph.size();
+    let max_size = max_size.min(LIMIT);
+
+    if n > max_size {
+        return Err(TooLargeError { size: n, max_size });
+    }
+
+    if n < 2 {
+        return Ok(vec![]);
+    }
+
+    let free = n - 1;
+    let task_bits = free.min(8);
+    let low_bits = free - task_bits;
+    let tasks: Vec<u64> = (0..1u64 << task_bits).collect();
+    let sides_of = |mask: u64| -> Vec<bool> { (0..n).map(|v| v < free && mask >> v & 1 == 1).collect() };
+
+    let best = parallel::map(&tasks, |task| {
+        if !control.proceed() {
+            return None;
+        }
+
+        let mut sides = sides_of(task << low_bits);
+        let mut value = graph.cut(&sides).len() as i64;
+        let mut best = (value, 0u64);
+
+        for step in 1..1u64 << low_bits {
+            if step % CHECK_INTERVAL == 0 && control.stop().is_some() {
+                break;
+            }
+
+            let vertex = step.trailing_zeros() as usize;
+
+            // Edges to the own side become cut, the others uncut, self-loops stay uncut
+            for neigh in graph.neighbors(vertex).iter().filter(|neigh| **neigh != vertex) {
+                value += if sides[*neigh] == sides[vertex] { 1 } else { -1 };
+            }
+            sides[vertex] = !sides[vertex];
+
+            if value > best.0 {
+                best = (value, step ^ (step >> 1));
+            }
+        }
+
+        let best = (best.0, best.1 | task << low_bits);
+        if best.0 as usize > control.best() {
+            control.improve(&graph.cut(&sides_of(best.1)));
+        }
+
+        Some(best)
+    });
+
+    let mask = best
+        .into_iter()
+        .flatten()
+        .max_by_key(|(value, _)| *value)
+        .map_or(0, |(_, mask)| mask);
+
+    Ok(graph.cut(&sides_of(mask)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+
+    #[test]
+    fn known_values() {
+        let mut k4 = Graph::new_empty(4);
+        k4.add_edges(&[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+
+        let mut petersen = Graph::new_empty(10);
+        for i in 0..5 {
+            petersen.add_edges(&[(i, (i + 1) % 5), (i, i + 5), (i + 5, (i + 2) % 5 + 5)]);
+        }
+
+        let mut cycle = Graph::new_empty(13);
+        for i in 0..13 {
+            cycle.add_edge(&(i, (i + 1) % 13));
+        }
+
+        for (graph, value) in [(k4, 4), (petersen, 12), (cycle, 12)] {
+            let cut = max_cut_brute_force(&graph, LIMIT).unwrap();
+
+            assert!(graph.partition(&cut).is_some());
+            assert_eq!(cut.len(), value);
+        }
+    }
+
+    #[test]
+    fn self_loops_are_never_cut() {
+        for seed in 0..4 {
+            let mut graph = generate::gnp(16, 0.3, seed);
+            graph.add_edges(&[(0, 0), (1, 1)]);
+
+            let best = (0..1u64 << 15)
+                .map(|mask| graph.cut(&(0..16).map(|v| mask >> v & 1 == 1).collect::<Vec<_>>()).len())
+                .max();
+
+            assert_eq!(Some(max_cut_brute_force(&graph, LIMIT).unwrap().len()), best, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn refuses_large_graphs() {
+        let graph = Graph::new_empty(21);
+
+        assert_eq!(
+            max_cut_brute_force(&graph, 20),
+            Err(TooLargeError {
+                size: 21,
+                max_size: 20
+            })
+        );
+        assert!(max_cut_brute_force(&Graph::new_empty(1), 20).unwrap().is_empty());
+    }
+
+    #[test]
+    fn stops_with_best_cut_so_far() {
+        let mut cycle = Graph::new_empty(12);
+        for i in 0..12 {
+            cycle.add_edge(&(i, (i + 1) % 12));
+        }
+
+        let control = RunControl::new().with_iteration_limit(1);
+        let cut = max_cut_brute_force_controlled(&cycle, LIMIT, &control).unwrap();
+        assert!(cycle.partition(&cut).is_some());
+        assert_eq!(control.iterations(), 1);
+
+        let control = RunControl::new().with_target(12);
+        assert_eq!(max_cut_brute_force_controlled(&cycle, LIMIT, &control).unwrap().len(), 12);
+        assert!(control.iterations() < 1 << 8);
+    }
+}
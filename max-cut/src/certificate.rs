@@ -0,0 +1,293 @@
+//! Solution files that can be checked independently of the solver.
+//!
+//! A solution file is line based, vertices are numbered from 1 like in the
+//! graph formats:
+//!
+//! ```text
+//! c any comment
+//! s 12                  value of the cut
+//! m instance 0f3c...    FNV-1a hash of the graph, see `instance_hash`
+//! m algorithm planar    algorithm that found the cut, the rest of the line
+//! m bound 12            upper bound for the maximum cut known to the solver
+//! v 1 0                 side 0 or 1 of vertex 1
+//! v 2 1
+//! ```
+//!
+//! The `m` lines are optional, every vertex needs exactly one `v` line.
+
+use std::fmt;
+
+use crate::graph::{Edge, Graph};
+
+/// A bipartition of a graph together with the claims made about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    pub sides: Vec<bool>,
+    /// Claimed number of cut edges
+    pub value: usize,
+    pub instance: Option<u64>,
+    pub algorithm: Option<String>,
+    pub bound: Option<usize>,
+}
+
+impl Certificate {
+    /// Certificate for the cut `cut` of `graph`.
+    /// Returns `None` if no bipartition induces `cut`.
+    pub fn new(graph: &Graph, cut: &[Edge]) -> Option<Certificate> {
+        Some(Certificate {
+            sides: graph.partition(cut)?,
+            value: cut.len(),
+            instance: Some(instance_hash(graph)),
+            algorithm: None,
+            bound: None,
+        })
+    }
+
+    /// Parses a solution file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first malformed or missing line.
+    pub fn parse(raw: &str) -> Result<Certificate, String> {
+        let mut value = None;
+        let mut assigned: Vec<(usize, bool)> = vec![];
+        let mut certificate = Certificate {
+            sides: vec![],
+            value: 0,
+            instance: None,
+            algorithm: None,
+            bound: None,
+        };
+
+        for line in raw.lines() {
+            let elems: Vec<&str> = line.split_whitespace().collect();
+            let unrecognized = || format!("unrecognized line: \'{line}\'");
+
+            match elems[..] {
+                [] | ["c", ..] => continue,
+                ["s", v] => value = Some(v.parse().map_err(|_| unrecognized())?),
+                ["m", "instance", hash] => {
+                    certificate.instance =
+                        Some(u64::from_str_radix(hash, 16).map_err(|_| unrecognized())?)
+                }
+                // Names like 'greedy-ordered(bfs 0)' contain spaces
+                ["m", "algorithm", _, ..] => certificate.algorithm = Some(elems[2..].join(" ")),
+                ["m", "bound", bound] => {
+                    certificate.bound = Some(bound.parse().map_err(|_| unrecognized())?)
+                }
+                ["v", vertex, side] => {
+                    let vertex: usize = vertex.parse().map_err(|_| unrecognized())?;
+                    let side = match side {
+                        "0" => false,
+                        "1" => true,
+                        _ => return Err(unrecognized()),
+                    };
+
+                    if vertex == 0 {
+                        return Err(unrecognized());
+                    }
+                    assigned.push((vertex, side));
+                }
+                _ => return Err(unrecognized()),
+            }
+        }
+
+        certificate.value = value.ok_or_else(|| "no s-line".to_owned())?;
+
+        // Vertex numbers come from the file, so they may only size the sides
+        // once every vertex below them has a line
+        let size = assigned.iter().map(|(vertex, _)| *vertex).max().unwrap_or(0);
+        if size > assigned.len() {
+            let mut vertices: Vec<usize> = assigned.iter().map(|(vertex, _)| *vertex).collect();
+            vertices.sort_unstable();
+            vertices.dedup();
+            let missing = (1..).zip(&vertices).find(|(expected, vertex)| *expected != **vertex);
+            return Err(format!("vertex {} has no side", missing.map_or(size, |(expected, _)| expected)));
+        }
+
+        let mut sides: Vec<Option<bool>> = vec![None; size];
+        for (vertex, side) in assigned {
+            if sides[vertex - 1].replace(side).is_some() {
+                return Err(format!("vertex {vertex} has more than one side"));
+            }
+        }
+
+        certificate.sides = sides
+            .iter()
+            .enumerate()
+            .map(|(vertex, side)| side.ok_or_else(|| format!("vertex {} has no side", vertex + 1)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(certificate)
+    }
+
+    /// Recomputes the cut of the bipartition on `graph` and checks every
+    /// claim of the certificate.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of every claim that doesn't hold.
+    pub fn verify(&self, graph: &Graph) -> Result<usize, Vec<String>> {
+        if self.sides.len() != graph.size() {
+            return Err(vec![format!(
+                "solution has {} vertices, graph has {}",
+                self.sides.len(),
+                graph.size()
+            )]);
+        }
+
+        let value = graph.cut(&self.sides).len();
+        let mut mismatches = vec![];
+
+        if value != self.value {
+            mismatches.push(format!("claimed value {} but the cut has {value} edges", self.value));
+        }
+
+        if let Some(instance) = self.instance {
+            if instance != instance_hash(graph) {
+                mismatches.push(format!(
+                    "instance hash {instance:016x} differs from {:016x} of the graph",
+                    instance_hash(graph)
+                ));
+            }
+        }
+
+        if let Some(bound) = self.bound {
+            if value > bound {
+                mismatches.push(format!("cut of {value} edges exceeds the bound {bound}"));
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(value)
+        } else {
+            Err(mismatches)
+        }
+    }
+}
+
+impl fmt::Display for Certificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "c max-cut solution")?;
+        writeln!(f, "s {}", self.value)?;
+
+        if let Some(instance) = self.instance {
+            writeln!(f, "m instance {instance:016x}")?;
+        }
+        if let Some(algorithm) = &self.algorithm {
+            writeln!(f, "m algorithm {algorithm}")?;
+        }
+        if let Some(bound) = self.bound {
+            writeln!(f, "m bound {bound}")?;
+        }
+
+        for (vertex, side) in self.sides.iter().enumerate() {
+            writeln!(f, "v {} {}", vertex + 1, *side as u8)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// FNV-1a hash of the number of vertices and the sorted edge list of `graph`.
+/// It doesn't depend on the file format or the order of the edges.
+pub fn instance_hash(graph: &Graph) -> u64 {
+    let mut edges: Vec<(usize, usize)> = graph.all_edges().iter().map(|e| (e.0, e.1)).collect();
+    edges.sort_unstable();
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |value: usize| {
+        for byte in (value as u64).to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+
+    feed(graph.size());
+    for (u, v) in edges {
+        feed(u);
+        feed(v);
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::VertexOrder;
+    use crate::solver::Algorithm;
+
+    fn triangle() -> Graph {
+        let mut graph = Graph::new_empty(3);
+        graph.add_edges(&[(0, 1), (1, 2), (0, 2)]);
+        graph
+    }
+
+    #[test]
+    fn round_trip() {
+        let graph = triangle();
+        let mut certificate = Certificate::new(&graph, &[Edge(0, 1), Edge(1, 2)]).unwrap();
+        certificate.algorithm = Some("planar".to_owned());
+        certificate.bound = Some(2);
+
+        let parsed = Certificate::parse(&certificate.to_string()).unwrap();
+
+        assert_eq!(parsed, certificate);
+        assert_eq!(parsed.verify(&graph), Ok(2));
+    }
+
+    #[test]
+    fn algorithm_names_round_trip() {
+        let algorithms = [
+            Algorithm::Ilp,
+            Algorithm::Greedy,
+            Algorithm::GreedyImproved,
+            Algorithm::GreedyOrdered {
+                order: VertexOrder::Bfs,
+                passes: 0,
+            },
+            Algorithm::Random,
+            Algorithm::RandomImproved,
+            Algorithm::RandomParallel,
+            Algorithm::Derandomized,
+            Algorithm::Spectral,
+            Algorithm::Trevisan,
+            Algorithm::Oct { max_exact: 12 },
+            Algorithm::Planar,
+            Algorithm::Treewidth { max_width: 16 },
+            Algorithm::BruteForce { max_size: 30 },
+        ];
+        let graph = triangle();
+
+        for algorithm in algorithms {
+            let mut certificate = Certificate::new(&graph, &[Edge(0, 1), Edge(1, 2)]).unwrap();
+            certificate.algorithm = Some(algorithm.to_string());
+
+            assert_eq!(Certificate::parse(&certificate.to_string()), Ok(certificate));
+        }
+    }
+
+    #[test]
+    fn mismatches() {
+        let graph = triangle();
+        let raw = "s 3\nm instance 0\nv 1 0\nv 2 1\nv 3 0\n";
+        let certificate = Certificate::parse(raw).unwrap();
+
+        assert_eq!(certificate.verify(&graph).unwrap_err().len(), 2);
+        assert!(Certificate::parse("s 1\nv 1 0\nv 3 1\n").is_err());
+        assert!(Certificate::parse("v 1 0\n").is_err());
+        assert_eq!(
+            Certificate::parse("s 1\nv 1 0\nv 99999999999999 1\n"),
+            Err("vertex 2 has no side".to_owned())
+        );
+        assert_eq!(
+            Certificate::parse("s 1\nv 1 0\nv 1 1\n"),
+            Err("vertex 1 has more than one side".to_owned())
+        );
+
+        let mut reversed = Graph::new_empty(3);
+        reversed.add_edges(&[(2, 0), (2, 1), (1, 0)]);
+        assert_eq!(instance_hash(&graph), instance_hash(&reversed));
+    }
+}
@@ -0,0 +1,355 @@
+//! Stopping and observing long running algorithms.
+//!
+//! A [`RunControl`] is passed by reference to an algorithm, which calls
+//! [`RunControl::proceed`] before every iteration and reports every improved
+//! cut to [`RunControl::improve`], improved upper bounds to
+//! [`RunControl::bound`] and restarts to [`RunControl::restart`]. Once the
+//! time or iteration budget is used up, the target value is reached or the run
+//! is cancelled, the algorithm stops and returns the best cut it has found so far.
+//!
+//! What an iteration is depends on the algorithm, see [`crate::solver::Algorithm::solve_controlled`].
+//! The values reported and the target refer to the graph the algorithm runs
+//! on, so parts of a graph like blocks or kernels need a control of their own.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::graph::Edge;
+
+/// A cut improving on all cuts reported before.
+#[derive(Debug, Clone, Copy)]
+pub struct Incumbent<'a> {
+    pub cut: &'a [Edge],
+    pub value: usize,
+    /// Time since the [`RunControl`] was created
+    pub elapsed: Duration,
+    /// Iterations started so far
+    pub iteration: u64,
+}
+
+/// Progress of a run, passed to the callbacks of [`RunControl::on_event`].
+#[derive(Debug, Clone, Copy)]
+pub enum Event<'a> {
+    Incumbent(Incumbent<'a>),
+    /// No cut has more than `value` edges
+    Bound { value: usize, elapsed: Duration },
+    /// The algorithm starts over from a new solution
+    Restart { iteration: u64, elapsed: Duration },
+}
+
+/// Why a [`RunControl`] asks to stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Cancelled,
+    TimeLimit,
+    IterationLimit,
+    /// A cut of at least the target value was found
+    Target,
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stop::Cancelled => write!(f, "cancelled"),
+            Stop::TimeLimit => write!(f, "time limit reached"),
+            Stop::IterationLimit => write!(f, "iteration limit reached"),
+            Stop::Target => write!(f, "target reached"),
+        }
+    }
+}
+
+type EventCallback = Box<dyn Fn(&Event) + Send + Sync>;
+
+/// Budgets, target and cancellation of a run, shared by all threads of an algorithm.
+pub struct RunControl {
+    start: Instant,
+    time_limit: Option<Duration>,
+    iteration_limit: Option<u64>,
+    /// `usize::MAX` without a target
+    target: AtomicUsize,
+    cancel: Arc<AtomicBool>,
+    iterations: AtomicU64,
+    best: AtomicUsize,
+    bound: AtomicUsize,
+    callbacks: Vec<EventCallback>,
+}
+
+impl RunControl {
+    /// A control without any limit that never stops a run on its own.
+    pub fn new() -> RunControl {
+        RunControl {
+            start: Instant::now(),
+            time_limit: None,
+            iteration_limit: None,
+            target: AtomicUsize::new(usize::MAX),
+            cancel: Arc::new(AtomicBool::new(false)),
+            iterations: AtomicU64::new(0),
+            best: AtomicUsize::new(0),
+            bound: AtomicUsize::new(usize::MAX),
+            callbacks: vec![],
+        }
+    }
+
+    /// Stops the run `time_limit` after the creation of the control.
+    pub fn with_time_limit(mut self, time_limit: Duration) -> RunControl {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Stops the run after `iterations` iterations.
+    pub fn with_iteration_limit(mut self, iterations: u64) -> RunControl {
+        self.iteration_limit = Some(iterations);
+        self
+    }
+
+    /// Stops the run once a cut of at least `target` edges is found.
+    pub fn with_target(mut self, target: usize) -> RunControl {
+        self.target = AtomicUsize::new(target);
+        self
+    }
+
+    /// Stops the run once `cancel` is set, e.g. from another thread.
+    pub fn with_cancel(mut self, cancel: Arc<AtomicBool>) -> RunControl {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Calls `callback` for every improved cut. It runs on the thread of the
+    /// algorithm that found the cut and should return quickly.
+    pub fn on_incumbent<F>(self, callback: F) -> RunControl
+    where
+        F: Fn(&Incumbent) + Send + Sync + 'static,
+    {
+        self.on_event(move |event| {
+            if let Event::Incumbent(incumbent) = event {
+                callback(incumbent);
+            }
+        })
+    }
+
+    /// Calls `callback` for every [`Event`], like [`Self::on_incumbent`].
+    pub fn on_event<F>(mut self, callback: F) -> RunControl
+    where
+        F: Fn(&Event) + Send + Sync + 'static,
+    {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Lowers the target to `target` if it is smaller, for algorithms that are
+    /// done once they reach a value of their own.
+    pub fn lower_target(&self, target: usize) {
+        self.target.fetch_min(target, Ordering::Relaxed);
+    }
+
+    /// The target value, `None` if there is none.
+    pub fn target(&self) -> Option<usize> {
+        Some(self.target.load(Ordering::Relaxed)).filter(|target| *target != usize::MAX)
+    }
+
+    /// The flag that cancels the run when set.
+    pub fn cancel_token(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.cancel)
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Time left until the time limit, `None` without a time limit.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.time_limit
+            .map(|time_limit| time_limit.saturating_sub(self.elapsed()))
+    }
+
+    pub fn iterations(&self) -> u64 {
+        self.iterations.load(Ordering::Relaxed)
+    }
+
+    /// Value of the best cut reported so far.
+    pub fn best(&self) -> usize {
+        self.best.load(Ordering::Relaxed)
+    }
+
+    /// Smallest upper bound reported so far, `None` if there is none.
+    pub fn upper_bound(&self) -> Option<usize> {
+        Some(self.bound.load(Ordering::Relaxed)).filter(|bound| *bound != usize::MAX)
+    }
+
+    /// Why the run should stop, `None` if it may go on.
+    pub fn stop(&self) -> Option<Stop> {
+        if self.cancel.load(Ordering::Relaxed) {
+            Some(Stop::Cancelled)
+        } else if self.target().is_some_and(|target| self.best() >= target) {
+            Some(Stop::Target)
+        } else if self.remaining().is_some_and(|remaining| remaining.is_zero()) {
+            Some(Stop::TimeLimit)
+        } else if self
+            .iteration_limit
+            .is_some_and(|limit| self.iterations() >= limit)
+        {
+            Some(Stop::IterationLimit)
+        } else {
+            None
+        }
+    }
+
+    /// Starts an iteration if the run may go on.
+    /// Returns `false` if the algorithm has to stop instead.
+    pub fn proceed(&self) -> bool {
+        if self.stop().is_some() {
+            return false;
+        }
+
+        // Never counts beyond the limit, even with many threads
+        self.iterations
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |iteration| {
+                self.iteration_limit
+                    .is_none_or(|limit| iteration < limit)
+                    .then_some(iteration + 1)
+            })
+            .is_ok()
+    }
+
+    /// Reports `cut` to the callbacks if it is larger than every cut before.
+    pub fn improve(&self, cut: &[Edge]) {
+        let previous = self.best.fetch_max(cut.len(), Ordering::Relaxed);
+
+        if cut.len() > previous {
+            self.emit(&Event::Incumbent(Incumbent {
+                cut,
+                value: cut.len(),
+                elapsed: self.elapsed(),
+                iteration: self.iterations(),
+            }));
+        }
+    }
+
+    /// Reports the upper bound `value` to the callbacks if it is smaller than
+    /// every bound before.
+    pub fn bound(&self, value: usize) {
+        let previous = self.bound.fetch_min(value, Ordering::Relaxed);
+
+        if value < previous {
+            self.emit(&Event::Bound {
+                value,
+                elapsed: self.elapsed(),
+            });
+        }
+    }
+
+    /// Reports that the algorithm starts over from a new solution.
+    pub fn restart(&self) {
+        if !self.callbacks.is_empty() {
+            self.emit(&Event::Restart {
+                iteration: self.iterations(),
+                elapsed: self.elapsed(),
+            });
+        }
+    }
+
+    fn emit(&self, event: &Event) {
+        for callback in &self.callbacks {
+            callback(event);
+        }
+    }
+}
+
+impl Default for RunControl {
+    fn default() -> Self {
+        RunControl::new()
+    }
+}
+
+impl fmt::Debug for RunControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RunControl")
+            .field("time_limit", &self.time_limit)
+            .field("iteration_limit", &self.iteration_limit)
+            .field("target", &self.target())
+            .field("iterations", &self.iterations())
+            .field("best", &self.best())
+            .field("upper_bound", &self.upper_bound())
+            .field("stop", &self.stop())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn limits() {
+        let control = RunControl::new().with_iteration_limit(3);
+        assert_eq!((0..10).filter(|_| control.proceed()).count(), 3);
+        assert_eq!(control.stop(), Some(Stop::IterationLimit));
+
+        let control = RunControl::new().with_time_limit(Duration::ZERO);
+        assert!(!control.proceed());
+        assert_eq!(control.stop(), Some(Stop::TimeLimit));
+
+        let control = RunControl::new();
+        let token = control.cancel_token();
+        assert!(control.proceed());
+        token.store(true, Ordering::Relaxed);
+        assert_eq!(control.stop(), Some(Stop::Cancelled));
+    }
+
+    #[test]
+    fn incumbents() {
+        let values = Arc::new(Mutex::new(vec![]));
+        let seen = Arc::clone(&values);
+        let control = RunControl::new()
+            .with_target(3)
+            .on_incumbent(move |incumbent| seen.lock().unwrap().push(incumbent.value));
+
+        let cut = [Edge(0, 1), Edge(1, 2), Edge(2, 3)];
+        control.improve(&cut[..2]);
+        control.improve(&cut[..1]);
+        assert!(control.proceed());
+        control.improve(&cut);
+
+        assert_eq!(*values.lock().unwrap(), vec![2, 3]);
+        assert_eq!(control.stop(), Some(Stop::Target));
+
+        let control = RunControl::new().with_target(5);
+        control.lower_target(7);
+        assert_eq!(control.target(), Some(5));
+        control.lower_target(1);
+        control.improve(&cut[..1]);
+        assert_eq!(control.stop(), Some(Stop::Target));
+    }
+
+    #[test]
+    fn events() {
+        let events = Arc::new(Mutex::new(vec![]));
+        let seen = Arc::clone(&events);
+        let control = RunControl::new().on_event(move |event| {
+            seen.lock().unwrap().push(match event {
+                Event::Incumbent(incumbent) => format!("incumbent {}", incumbent.value),
+                Event::Bound { value, .. } => format!("bound {value}"),
+                Event::Restart { iteration, .. } => format!("restart {iteration}"),
+            })
+        });
+
+        control.bound(5);
+        control.restart();
+        control.improve(&[Edge(0, 1)]);
+        control.bound(7);
+        control.bound(4);
+
+        assert_eq!(control.upper_bound(), Some(4));
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec!["bound 5", "restart 0", "incumbent 1", "bound 4"]
+        );
+    }
+}
@@ -0,0 +1,293 @@
+//! Splitting Max-Cut instances into independent pieces.
+//!
+//! A maximum cut of a graph is the union of maximum cuts of its connected
+//! components. The same holds for its blocks (biconnected components):
+//! two blocks share at most one cut vertex and the sides of a block can
+//! always be swapped so that it agrees with its neighbours on that vertex.
+
+use crate::graph::{Edge, Graph};
+use crate::parallel;
+
+/// Returns the vertex sets of the connected components of `graph`,
+/// every set sorted ascending.
+pub fn connected_components(graph: &Graph) -> Vec<Vec<usize>> {
+    let mut visited = vec![false; graph.size()];
+    let mut components = vec![];
+
+    for start in 0..graph.size() {
+        if visited[start] {
+            continue;
+        }
+
+        visited[start] = true;
+        let mut component = vec![start];
+        let mut i = 0;
+
+        while i < component.len() {
+            let vertex = component[i];
+            i += 1;
+
+            for neigh in graph.neighbors(vertex) {
+                if !visited[*neigh] {
+                    visited[*neigh] = true;
+                    component.push(*neigh);
+                }
+            }
+        }
+
+        component.sort_unstable();
+        components.push(component);
+    }
+
+    components
+}
+
+/// Returns the vertex sets of the blocks of `graph`, every set sorted ascending.
+/// Every edge belongs to exactly one block, isolated vertices belong to none.
+pub fn blocks(graph: &Graph) -> Vec<Vec<usize>> {
+    let n = graph.size();
+    let mut discovered = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut time = 0;
+    let mut edges: Vec<Edge> = vec![];
+    let mut blocks = vec![];
+
+    for root in 0..n {
+        if discovered[root] != usize::MAX {
+            continue;
+        }
+
+        discovered[root] = time;
+        low[root] = time;
+        time += 1;
+
+        // Iterative DFS, every frame holds (vertex, parent, next neighbor)
+        let mut stack = vec![(root, usize::MAX, 0usize)];
+
+        while let Some(frame) = stack.last_mut() {
+            let (vertex, parent) = (frame.0, frame.1);
+            let neighbors = graph.neighbors(vertex);
+
+            if frame.2 < neighbors.len() {
+                let neigh = neighbors[frame.2];
+                frame.2 += 1;
+
+                if discovered[neigh] == usize::MAX {
+                    discovered[neigh] = time;
+                    low[neigh] = time;
+                    time += 1;
+                    edges.push(Edge(vertex, neigh));
+                    stack.push((neigh, vertex, 0));
+                } else if neigh != parent && discovered[neigh] < discovered[vertex] {
+                    low[vertex] = low[vertex].min(discovered[neigh]);
+                    edges.push(Edge(vertex, neigh));
+                }
+
+                continue;
+            }
+
+            stack.pop();
+
+            if let Some(&(parent, _, _)) = stack.last() {
+                low[parent] = low[parent].min(low[vertex]);
+
+                if low[vertex] >= discovered[parent] {
+                    let mut block = vec![];
+
+                    while let Some(edge) = edges.pop() {
+                        block.push(edge.0);
+                        block.push(edge.1);
+
+                        if edge == Edge(parent, vertex) {
+                            break;
+                        }
+                    }
+
+                    block.sort_unstable();
+                    block.dedup();
+                    blocks.push(block);
+                }
+            }
+        }
+    }
+
+    blocks
+}
+
+/// Returns the cut vertices of `graph` in ascending order,
+/// i.e. the vertices belonging to more than one block.
+pub fn cut_vertices(graph: &Graph) -> Vec<usize> {
+    let mut count = vec![0usize; graph.size()];
+
+    blocks(graph)
+        .iter()
+        .flatten()
+        .for_each(|vertex| count[*vertex] += 1);
+
+    (0..graph.size()).filter(|vertex| count[*vertex] > 1).collect()
+}
+
+/// Solves every connected component of `graph` separately and in parallel
+/// with `solver` and returns the union of the cuts.
+///
+/// # Errors
+///
+/// Returns the first error `solver` returned for any component.
+pub fn solve_components<F, E>(graph: &Graph, solver: F) -> Result<Vec<Edge>, E>
+where
+    F: Fn(&Graph) -> Result<Vec<Edge>, E> + Sync,
+    E: Send,
+{
+    let components: Vec<Vec<usize>> = connected_components(graph)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .collect();
+
+    let partitions = solve_pieces(graph, &components, &solver)?;
+    let mut sides = vec![false; graph.size()];
+
+    for (component, partition) in components.iter().zip(partitions) {
+        for (vertex, side) in component.iter().zip(partition) {
+            sides[*vertex] = side;
+        }
+    }
+
+    Ok(graph.cut(&sides))
+}
+
+/// Solves every block of `graph` separately and in parallel with `solver`
+/// and stitches the partitions together at the cut vertices.
+/// If `solver` is exact, so is the result.
+///
+/// # Errors
+///
+/// Returns the first error `solver` returned for any block.
+pub fn solve_blocks<F, E>(graph: &Graph, solver: F) -> Result<Vec<Edge>, E>
+where
+    F: Fn(&Graph) -> Result<Vec<Edge>, E> + Sync,
+    E: Send,
+{
+    let blocks = blocks(graph);
+    let partitions = solve_pieces(graph, &blocks, &solver)?;
+
+    let mut blocks_of = vec![vec![]; graph.size()];
+    for (i, block) in blocks.iter().enumerate() {
+        block.iter().for_each(|vertex| blocks_of[*vertex].push(i));
+    }
+
+    // Walk the block-cut tree. Every block reached this way shares exactly one
+    // vertex with the blocks placed before, so flipping it never breaks them.
+    let mut sides: Vec<Option<bool>> = vec![None; graph.size()];
+    let mut reached = vec![false; blocks.len()];
+    let mut queue = vec![];
+
+    for start in 0..blocks.len() {
+        if reached[start] {
+            continue;
+        }
+
+        reached[start] = true;
+        queue.push(start);
+
+        while let Some(i) = queue.pop() {
+            let block = blocks[i].iter().zip(&partitions[i]);
+
+            let flip = block
+                .clone()
+                .find_map(|(vertex, side)| sides[*vertex].map(|placed| placed != *side))
+                .unwrap_or(false);
+
+            for (vertex, side) in block {
+                sides[*vertex] = Some(side ^ flip);
+
+                for next in &blocks_of[*vertex] {
+                    if !reached[*next] {
+                        reached[*next] = true;
+                        queue.push(*next);
+                    }
+                }
+            }
+        }
+    }
+
+    let sides: Vec<bool> = sides.into_iter().map(|side| side.unwrap_or(false)).collect();
+
+    Ok(graph.cut(&sides))
+}
+
+/// Solves the subgraphs induced by `pieces` in parallel and returns their
+/// partitions, indexed like the vertices of the pieces.
+fn solve_pieces<F, E>(graph: &Graph, pieces: &[Vec<usize>], solver: &F) -> Result<Vec<Vec<bool>>, E>
+where
+    F: Fn(&Graph) -> Result<Vec<Edge>, E> + Sync,
+    E: Send,
+{
+    parallel::map(pieces, |piece| {
+        let subgraph = graph.induced_subgraph(piece);
+
+        // A single edge is always cut, no need to bother the solver
+        let cut = if piece.len() <= 2 {
+            subgraph.all_edges()
+        } else {
+            solver(&subgraph)?
+        };
+
+        Ok(subgraph
+            .partition(&cut)
+            .expect("solver returned a set of edges that is no cut"))
+    })
+    .into_iter()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brute_force::{self, LIMIT};
+
+    /// Two triangles sharing vertex 2, a pendant vertex 5 and an isolated vertex 6
+    /// plus the separate edge (7, 8).
+    fn bowtie() -> Graph {
+        let mut graph = Graph::new_empty(9);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2), (4, 5), (7, 8)]);
+        graph
+    }
+
+    #[test]
+    fn components() {
+        assert_eq!(
+            connected_components(&bowtie()),
+            vec![vec![0, 1, 2, 3, 4, 5], vec![6], vec![7, 8]]
+        );
+    }
+
+    #[test]
+    fn blocks_and_cut_vertices() {
+        let mut found = blocks(&bowtie());
+        found.sort();
+
+        assert_eq!(found, vec![vec![0, 1, 2], vec![2, 3, 4], vec![4, 5], vec![7, 8]]);
+        assert_eq!(cut_vertices(&bowtie()), vec![2, 4]);
+    }
+
+    #[test]
+    fn solve_blocks_is_optimal() {
+        let graph = bowtie();
+        let cut = solve_blocks(&graph, |block| brute_force::max_cut_brute_force(block, LIMIT)).unwrap();
+
+        assert_eq!(cut.len(), brute_force::max_cut_brute_force(&graph, LIMIT).unwrap().len());
+        assert!(graph.partition(&cut).is_some());
+    }
+
+    #[test]
+    fn solve_components_is_optimal() {
+        let graph = bowtie();
+        let cut = solve_components(&graph, |component| {
+            brute_force::max_cut_brute_force(component, LIMIT)
+        })
+        .unwrap();
+
+        assert_eq!(cut.len(), 6);
+        assert!(graph.partition(&cut).is_some());
+    }
+}
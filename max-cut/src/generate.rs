@@ -0,0 +1,78 @@
+//! Seeded random graphs, the same seed always gives the same graph.
+
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::graph::Graph;
+
+/// Erdős–Rényi graph on `n` vertices containing every edge with probability `p`.
+pub fn gnp(n: usize, p: f64, seed: u64) -> Graph {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut graph = Graph::new_empty(n);
+
+    for u in 0..n {
+        for v in u + 1..n {
+            if rng.gen_bool(p) {
+                graph.add_edge(&(u, v));
+            }
+        }
+    }
+
+    graph
+}
+
+/// Uniformly random graph on `n` vertices with `m` edges.
+/// `m` is capped at the number of possible edges.
+pub fn gnm(n: usize, m: usize, seed: u64) -> Graph {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut graph = Graph::new_empty(n);
+    let m = m.min(n * n.saturating_sub(1) / 2);
+    let mut edges = 0;
+
+    while edges < m {
+        let u = rng.gen_range(0..n);
+        let v = rng.gen_range(0..n);
+
+        if u != v && !graph.contains_edge(&(u, v)) {
+            graph.add_edge(&(u, v));
+            edges += 1;
+        }
+    }
+
+    graph
+}
+
+/// `rows` times `columns` grid graph, planar and bipartite with treewidth
+/// `min(rows, columns)`.
+pub fn grid(rows: usize, columns: usize) -> Graph {
+    let mut graph = Graph::new_empty(rows * columns);
+
+    for row in 0..rows {
+        for column in 0..columns {
+            let vertex = row * columns + column;
+
+            if column + 1 < columns {
+                graph.add_edge(&(vertex, vertex + 1));
+            }
+            if row + 1 < rows {
+                graph.add_edge(&(vertex, vertex + columns));
+            }
+        }
+    }
+
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded() {
+        assert_eq!(gnp(20, 0.3, 7).all_edges(), gnp(20, 0.3, 7).all_edges());
+        assert_eq!(gnm(20, 45, 7).all_edges(), gnm(20, 45, 7).all_edges());
+        assert_eq!(gnm(20, 45, 7).edge_size(), 45);
+        assert_eq!(gnm(4, 100, 7).edge_size(), 6);
+        assert_eq!(grid(3, 4).edge_size(), 17);
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
+    collections::{HashMap, HashSet},
     fmt::{self, Debug},
-    usize,
 };
 
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -20,7 +20,7 @@ impl Debug for Edge {
 
 /// A graph type using an adjacency matrix.
 /// For simplicity, once created, only edges can be added or removed.
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Graph {
     // adjacency_matrix: Vec<Vec<bool>>,
     adjacency_lists: Vec<Vec<usize>>,
@@ -137,6 +137,89 @@ impl Graph {
     pub fn get_neighbors(&self, vertex: usize) -> Vec<usize> {
         self.adjacency_lists[vertex].clone()
     }
+
+    /// Borrowing variant of [`Graph::get_neighbors`].
+    pub fn neighbors(&self, vertex: usize) -> &[usize] {
+        &self.adjacency_lists[vertex]
+    }
+
+    /// Returns the subgraph induced by `vertices`.
+    /// Vertex `vertices[i]` becomes vertex `i` of the subgraph.
+    pub fn induced_subgraph(&self, vertices: &[usize]) -> Graph {
+        let index: HashMap<usize, usize> = vertices
+            .iter()
+            .enumerate()
+            .map(|(i, vertex)| (*vertex, i))
+            .collect();
+
+        let adjacency_lists = vertices
+            .iter()
+            .map(|vertex| {
+                self.adjacency_lists[*vertex]
+                    .iter()
+                    .filter_map(|neigh| index.get(neigh).copied())
+                    .collect()
+            })
+            .collect();
+
+        Graph { adjacency_lists }
+    }
+
+    /// Returns the edges cut by the bipartition `sides`,
+    /// where `sides[v]` tells on which side vertex `v` lies.
+    pub fn cut(&self, sides: &[bool]) -> Vec<Edge> {
+        self.all_edges()
+            .into_iter()
+            .filter(|edge| sides[edge.0] != sides[edge.1])
+            .collect()
+    }
+
+    /// Reconstructs a bipartition inducing exactly the edges in `cut`.
+    /// The lowest vertex of every connected component is put on side `false`.
+    ///
+    /// Returns `None` if `cut` contains duplicates or edges not in the graph,
+    /// or if no bipartition induces it.
+    pub fn partition(&self, cut: &[Edge]) -> Option<Vec<bool>> {
+        let cut: HashSet<(usize, usize)> = cut
+            .iter()
+            .map(|edge| (edge.0.min(edge.1), edge.0.max(edge.1)))
+            .collect();
+
+        if cut.iter().any(|edge| edge.1 >= self.size() || !self.contains_edge(edge))
+        {
+            return None;
+        }
+
+        let mut sides: Vec<Option<bool>> = vec![None; self.size()];
+        let mut stack = vec![];
+
+        for start in 0..self.size() {
+            if sides[start].is_some() {
+                continue;
+            }
+
+            sides[start] = Some(false);
+            stack.push((start, false));
+
+            while let Some((vertex, side)) = stack.pop() {
+                for neigh in &self.adjacency_lists[vertex] {
+                    let expected =
+                        side ^ cut.contains(&(vertex.min(*neigh), vertex.max(*neigh)));
+
+                    match sides[*neigh] {
+                        None => {
+                            sides[*neigh] = Some(expected);
+                            stack.push((*neigh, expected));
+                        }
+                        Some(other) if other != expected => return None,
+                        Some(_) => continue,
+                    }
+                }
+            }
+        }
+
+        sides.into_iter().collect()
+    }
 }
 
 #[cfg(test)]
@@ -178,4 +261,32 @@ mod tests {
         assert_eq!(graph.get_neighbors(0usize), vec![1usize, 2usize, 3usize]);
         assert_eq!(graph.get_neighbors(2usize), vec![0usize, 1usize])
     }
+
+    #[test]
+    fn induced_subgraph() {
+        let mut graph = Graph::new_empty(5);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+
+        let mut expected = Graph::new_empty(3);
+        expected.add_edges(&[(0, 1), (1, 2)]);
+
+        assert_eq!(graph.induced_subgraph(&[1, 2, 3]), expected);
+    }
+
+    #[test]
+    fn cut_and_partition() {
+        let mut graph = Graph::new_empty(5);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 3), (3, 0), (0, 2)]);
+
+        let sides = vec![false, true, false, true, false];
+        let cut = graph.cut(&sides);
+
+        assert_eq!(cut, vec![Edge(0, 1), Edge(0, 3), Edge(1, 2), Edge(2, 3)]);
+        assert_eq!(graph.partition(&cut), Some(sides));
+
+        // The triangle 0, 1, 2 can't have exactly one cut edge
+        assert_eq!(graph.partition(&[Edge(0, 1)]), None);
+        // (1, 3) is no edge of the graph
+        assert_eq!(graph.partition(&[Edge(1, 3)]), None);
+    }
 }
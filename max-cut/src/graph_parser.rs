@@ -41,7 +41,7 @@ pub fn parse_pace_graph(raw: &str) -> Result<Graph, String> {
     Ok(graph)
 }
 
-fn parse_line(line: &str) -> Result<ParsedLine, String> {
+fn parse_line(line: &str) -> Result<ParsedLine<'_>, String> {
     // Split line into it's elements
     let elems: Vec<&str> = line.split(' ').collect();
 
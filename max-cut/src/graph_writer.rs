@@ -0,0 +1,49 @@
+use std::fmt::Write;
+
+use crate::graph::Graph;
+
+/// Writes `graph` in the PACE format read by [`crate::graph_parser::parse_pace_graph`].
+pub fn write_pace_graph(graph: &Graph) -> String {
+    let edges = graph.all_edges();
+    let mut raw = format!("p cut {} {}\n", graph.size(), edges.len());
+
+    for edge in edges {
+        writeln!(raw, "{} {}", edge.0 + 1, edge.1 + 1).unwrap();
+    }
+
+    raw
+}
+
+/// Writes `graph` in the rudy format read by [`crate::graph_parser::parse_rudy`],
+/// every edge with weight 1.
+pub fn write_rudy(graph: &Graph) -> String {
+    let edges = graph.all_edges();
+    let mut raw = format!("{} {}\n", graph.size(), edges.len());
+
+    for edge in edges {
+        writeln!(raw, "{} {} 1", edge.0 + 1, edge.1 + 1).unwrap();
+    }
+
+    raw
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph_parser;
+
+    #[test]
+    fn round_trip() -> Result<(), String> {
+        let mut graph = Graph::new_empty(5);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 0), (3, 4)]);
+
+        let pace = graph_parser::parse_pace_graph(&write_pace_graph(&graph))?;
+        let rudy = graph_parser::parse_rudy(&write_rudy(&graph))?;
+
+        assert_eq!(pace.all_edges(), graph.all_edges());
+        assert_eq!(rudy.all_edges(), graph.all_edges());
+        assert_eq!(rudy.size(), 5);
+
+        Ok(())
+    }
+}
@@ -1,20 +1,35 @@
-use std::num::NonZeroUsize;
-use std::sync::{Arc, Mutex};
-use std::thread::{self, available_parallelism, Result};
+use std::ops::Range;
+use std::sync::Arc;
+use std::thread::Result;
 
+use crate::approx;
+use crate::control::RunControl;
 use crate::graph::{Edge, Graph};
+use crate::multistart::{self, MultiStart, StopReason};
+use crate::parallel;
 use rand::rngs::SmallRng;
 use rand::{Rng, SeedableRng};
 
+/// Fewest vertices per block of [`rand_aprox_parallel`].
+pub const MIN_BLOCK_SIZE: usize = 1 << 10;
+
+/// Masks evaluated by a task of [`rand_aprox_derandomized`].
+const MASKS_PER_TASK: usize = 16;
+
 pub fn rand_aprox(graph: &Graph) -> Vec<Edge> {
+    random_cut(graph, &mut SmallRng::from_entropy())
+}
+
+/// [`rand_aprox`] with the random bipartition given by `seed`.
+pub fn rand_aprox_seeded(graph: &Graph, seed: u64) -> Vec<Edge> {
+    random_cut(graph, &mut SmallRng::seed_from_u64(seed))
+}
+
+fn random_cut(graph: &Graph, rand: &mut SmallRng) -> Vec<Edge> {
     let mut s = vec![false; graph.size()];
-    let mut rand = SmallRng::from_entropy();
 
-    for vertex in 0..graph.size() {
-        match rand.gen_bool(1.0 / 2.0) {
-            true => s[vertex] = true,
-            false => continue,
-        }
+    for side in s.iter_mut() {
+        *side = rand.gen_bool(1.0 / 2.0);
     }
 
     let edges = graph.all_edges();
@@ -33,117 +48,171 @@ pub fn rand_aprox(graph: &Graph) -> Vec<Edge> {
 }
 
 pub fn rand_approx_impr(graph: Arc<Graph>) -> Result<Vec<Edge>> {
-    let cores = available_parallelism()
-        .unwrap_or(NonZeroUsize::new(8).unwrap())
-        .get();
-
-    let mut best = vec![];
-
-    while (best.len() as f64) < graph.edge_size() as f64 * 0.5 {
-        let mut handles = vec![];
-        for _core in 0..cores {
-            let graph = Arc::clone(&graph);
-            let handle = thread::spawn(move || rand_aprox(&graph));
-            handles.push(handle);
-        }
-
-        let mut results = vec![];
+    rand_approx_impr_controlled(graph, None, &RunControl::new())
+}
 
-        for handle in handles {
-            let result = handle.join()?;
-            results.push(result);
-        }
+/// [`rand_approx_impr`] with the random bipartitions given by `seed`.
+/// The result only depends on `seed` for a fixed number of cores.
+pub fn rand_approx_impr_seeded(graph: Arc<Graph>, seed: u64) -> Result<Vec<Edge>> {
+    rand_approx_impr_controlled(graph, Some(seed), &RunControl::new())
+}
 
-        for result in results {
-            if result.len() > best.len() {
-                best = result;
-            }
-        }
+/// [`rand_approx_impr`] that stops early when `control` asks to and then
+/// returns the best cut found so far, which may be less than half of the
+/// edges. Every random bipartition is one iteration and a restart. The target
+/// of `control` is lowered to half of the edges.
+///
+/// A random bipartition cuts at least half of the edges with probability
+/// `1 / (m / 2 + 1)` or more. After `4 (m + 1)` bipartitions without success
+/// the best one is improved by [`approx::local_search`] instead of
+/// running on, which also reaches half of the edges.
+pub fn rand_approx_impr_controlled(
+    graph: Arc<Graph>,
+    seed: Option<u64>,
+    control: &RunControl,
+) -> Result<Vec<Edge>> {
+    control.lower_target(graph.edge_size().div_ceil(2));
+    let mut search = MultiStart::new().with_starts(4 * (graph.edge_size() as u64 + 1));
+
+    if let Some(seed) = seed {
+        search = search.with_seed(seed);
     }
 
-    Ok(best)
-}
+    let mut result = search.run(&graph, &multistart::random_sides, None, control);
 
-pub fn rand_aprox_parallel(graph: &Graph) -> Result<Vec<Edge>> {
-    let s = Arc::new(Mutex::new(vec![false; graph.size()]));
-    let cores = available_parallelism()
-        .unwrap_or(NonZeroUsize::new(8).unwrap())
-        .get();
-    let mut slices = vec![vec![]; cores];
-    let mut core = 0;
-
-    for vertex in 0..graph.size() {
-        slices[core].push(vertex);
-        core = if core < cores - 1 { core + 1 } else { 0 };
+    if result.statistics.stop == Some(StopReason::Starts) && 2 * result.cut.len() < graph.edge_size() {
+        let vertices: Vec<usize> = (0..graph.size()).collect();
+        approx::local_search(&graph, &mut result.sides, &vertices, usize::MAX);
+        result.cut = graph.cut(&result.sides);
+        control.improve(&result.cut);
     }
 
-    let mut handles = vec![];
+    Ok(result.cut)
+}
 
-    for slice in slices {
-        let s = Arc::clone(&s);
-        let handle = thread::spawn(move || {
-            let mut rand = SmallRng::from_entropy();
+pub fn rand_aprox_parallel(graph: &Graph) -> Result<Vec<Edge>> {
+    rand_aprox_parallel_seeded(graph, SmallRng::from_entropy().gen())
+}
 
-            for vertex in slice {
-                match rand.gen_bool(1.0 / 2.0) {
-                    true => s.lock().unwrap()[vertex] = true,
-                    false => continue,
+/// [`rand_aprox_parallel`] with the random bipartition given by `seed`.
+///
+/// The side of every vertex is a bit of [`parallel::split_seed`] of `seed` and
+/// the vertex, so workers compute the sides of their vertices and neighbors
+/// themselves and share nothing. The vertices are split evenly among the cores
+/// into blocks of at least [`MIN_BLOCK_SIZE`], which are cut in a single round
+/// of workers. The result only depends on `seed` and not on the number of cores.
+pub fn rand_aprox_parallel_seeded(graph: &Graph, seed: u64) -> Result<Vec<Edge>> {
+    let block_size = graph.size().div_ceil(parallel::cores()).max(MIN_BLOCK_SIZE);
+    let blocks: Vec<Range<usize>> = (0..graph.size())
+        .step_by(block_size)
+        .map(|start| start..(start + block_size).min(graph.size()))
+        .collect();
+    let side = |vertex: usize| parallel::split_seed(seed, vertex as u64) & 1 == 1;
+
+    // Every block cuts the edges to its higher neighbors, in the order of `Graph::all_edges`
+    let cut = parallel::map(&blocks, |block| {
+        let mut cut = vec![];
+
+        for vertex in block.clone() {
+            let vertex_side = side(vertex);
+
+            for neigh in graph.neighbors(vertex) {
+                if *neigh > vertex && side(*neigh) != vertex_side {
+                    cut.push(Edge(vertex, *neigh));
                 }
             }
+        }
 
-            s
-        });
-
-        handles.push(handle);
-    }
-
-
-    for handle in handles {
-        handle.join()?;
-    }
-
-    let s = Arc::new(s.lock().unwrap().clone());
+        cut
+    })
+    .concat();
 
-    let edges = graph.all_edges();
-    let mut cut: Vec<Edge> = Vec::new();
-    let mut slices: Vec<Vec<Edge>> = vec![vec![]; cores];
-    let mut handles = vec![];
+    Ok(cut)
+}
 
-    for edge in edges {
-        slices[core].push(edge);
-        core = if core < cores - 1 { core + 1 } else { 0 };
-    }
+/// Deterministic counterpart of [`rand_aprox`] cutting at least half of the edges.
+///
+/// With `k` bits for the labels `1..=n` of the vertices, every mask `r` of `k`
+/// bits puts vertex `v` on the side given by the parity of `r & (v + 1)`. Over
+/// all `2^k < 2n` masks the sides of two distinct vertices differ for exactly
+/// half of the masks, so the average cut has `m / 2` edges and the best one at
+/// least as many. The masks are evaluated in parallel and of equal cuts the one
+/// of the lowest mask is returned.
+pub fn rand_aprox_derandomized(graph: &Graph) -> Vec<Edge> {
+    let bits = usize::BITS - graph.size().leading_zeros();
+    // An edge is cut by a mask iff the mask has odd overlap with the labels of its ends
+    let edges: Vec<u64> = graph
+        .all_edges()
+        .iter()
+        .map(|edge| ((edge.0 + 1) ^ (edge.1 + 1)) as u64)
+        .collect();
+
+    let masks = 1u64 << bits;
+    let chunks: Vec<Range<u64>> = (0..masks)
+        .step_by(MASKS_PER_TASK)
+        .map(|start| start..(start + MASKS_PER_TASK as u64).min(masks))
+        .collect();
+
+    let (_, mask) = parallel::map(&chunks, |chunk| {
+        chunk
+            .clone()
+            .map(|mask| (cut_value(&edges, mask), mask))
+            .fold((0, 0), |best, (value, mask)| if value > best.0 { (value, mask) } else { best })
+    })
+    .into_iter()
+    .fold((0, 0), |best, (value, mask)| if value > best.0 { (value, mask) } else { best });
+
+    let sides: Vec<bool> = (0..graph.size())
+        .map(|vertex| (mask & (vertex + 1) as u64).count_ones() % 2 == 1)
+        .collect();
+
+    graph.cut(&sides)
+}
 
-    for slice in slices {
-        let s = Arc::clone(&s);
-        let handle = thread::spawn(move || {
-            let mut cut = vec![];
-
-            for edge in slice {
-                let s_0 = s[edge.0];
-                let s_1 = s[edge.1];
-                if s_0 == s_1 {
-                    continue;
-                } else {
-                    cut.push(edge);
-                }
-            }
+fn cut_value(edges: &[u64], mask: u64) -> usize {
+    edges
+        .iter()
+        .filter(|edge| (*edge & mask).count_ones() % 2 == 1)
+        .count()
+}
 
-            cut
-        });
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
 
-        handles.push(handle);
-    }
+    #[test]
+    fn parallel_cut_is_reproducible() -> Result<()> {
+        let graph = generate::gnm(12 * MIN_BLOCK_SIZE, 48 * MIN_BLOCK_SIZE, 45);
+        let cut = rand_aprox_parallel_seeded(&graph, 7)?;
+        let sides = graph.partition(&cut).expect("not a cut");
 
-    let mut results = vec![];
+        assert_eq!(cut, graph.cut(&sides));
+        assert_eq!(cut, rand_aprox_parallel_seeded(&graph, 7)?);
+        assert_ne!(cut, rand_aprox_parallel_seeded(&graph, 8)?);
+        // A random bipartition cuts about half of the edges
+        assert!(cut.len().abs_diff(graph.edge_size() / 2) < graph.edge_size() / 20);
 
-    for handle in handles {
-        results.push(handle.join()?);
+        Ok(())
     }
 
-    for mut ele in results {
-        cut.append(&mut ele);
+    #[test]
+    fn derandomized_cut_is_half() {
+        let graph = generate::gnp(70, 0.15, 49);
+        let edges: Vec<u64> = graph
+            .all_edges()
+            .iter()
+            .map(|edge| ((edge.0 + 1) ^ (edge.1 + 1)) as u64)
+            .collect();
+
+        // Every edge is cut by exactly half of the 128 masks
+        let total: usize = (0..128).map(|mask| cut_value(&edges, mask)).sum();
+        assert_eq!(total, 64 * graph.edge_size());
+
+        let cut = rand_aprox_derandomized(&graph);
+        assert_eq!(cut, graph.cut(&graph.partition(&cut).expect("not a cut")));
+        assert_eq!(cut, rand_aprox_derandomized(&graph));
+        assert!(2 * cut.len() >= graph.edge_size());
+        assert_eq!(cut.len(), (0..128).map(|mask| cut_value(&edges, mask)).max().unwrap());
     }
-
-    Ok(cut)
 }
@@ -1,24 +1,78 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
 use good_lp::{
     constraint, default_solver, variable, Expression, ProblemVariables,
     SolverModel, Variable, Solution,
 };
 
+use crate::approx;
+use crate::bipartite;
+use crate::control::RunControl;
 use crate::graph::{Graph, Edge};
 
+/// Largest distance of a variable to an integer in a solution still taken as integral.
+const INTEGRALITY_TOLERANCE: f64 = 1e-6;
+
 pub struct MaxCutIlp<'a> {
     graph: &'a Graph,
     edges: Vec<Edge>,
 }
 
 impl<'a> MaxCutIlp<'a> {
-    pub fn new(graph: &Graph) -> MaxCutIlp {
+    pub fn new(graph: &Graph) -> MaxCutIlp<'_> {
         MaxCutIlp {
             graph,
             edges: graph.all_edges(),
         }
     }
 
+    /// Solves every component on its own.
+    /// Bipartite components are cut completely without building a model.
     pub fn solve(&self) -> Result<Vec<Edge>, good_lp::ResolutionError> {
+        self.solve_controlled(&RunControl::new())
+    }
+
+    /// [`Self::solve`] that hands the time left by `control` to the solver as
+    /// its time limit and returns the best cut found when it runs out.
+    /// Components not started when `control` stops are cut greedily, as are
+    /// components stopped before the solver found a better cut, so the cut is
+    /// only maximum if `control` didn't stop.
+    /// Every component solved to optimality lowers the upper bound reported to
+    /// `control` by its uncut edges.
+    pub fn solve_controlled(&self, control: &RunControl) -> Result<Vec<Edge>, good_lp::ResolutionError> {
+        let bound = AtomicUsize::new(self.edges.len());
+        control.bound(self.edges.len());
+
+        let cut = bipartite::solve(self.graph, |component| -> Result<Vec<Edge>, good_lp::ResolutionError> {
+            if !control.proceed() {
+                return Ok(approx::max_cut_greedy_impr(component));
+            }
+
+            let found = MaxCutIlp::new(component).solve_model(control.remaining())?;
+
+            // The solver may have stopped at its time limit, possibly without any solution
+            match (found, control.stop()) {
+                (Some(cut), None) => {
+                    let uncut = component.edge_size() - cut.len();
+                    control.bound(bound.fetch_sub(uncut, Ordering::Relaxed) - uncut);
+                    Ok(cut)
+                }
+                (None, None) => Err(good_lp::ResolutionError::Other("no integral solution")),
+                (found, Some(_)) => {
+                    let greedy = approx::max_cut_greedy_impr(component);
+                    Ok(found.filter(|cut| cut.len() > greedy.len()).unwrap_or(greedy))
+                }
+            }
+        })?;
+
+        control.improve(&cut);
+        Ok(cut)
+    }
+
+    /// Cut of the sides of the vertices in the solution of the solver, `None`
+    /// if they aren't integral, e.g. because the time limit was reached first.
+    fn solve_model(&self, time_limit: Option<Duration>) -> Result<Option<Vec<Edge>>, good_lp::ResolutionError> {
         let mut problem = ProblemVariables::new();
         let vertex_variables: Vec<Variable> = problem.add_vector(variable().binary(), self.graph.size());
         let edge_variables: Vec<Variable> = problem.add_vector(variable().binary(), self.edges.len());
@@ -31,15 +85,18 @@ impl<'a> MaxCutIlp<'a> {
             model = model.with(constraint!(*var <= 2 - (vertex_variables[edge.0] + vertex_variables[edge.1])));
         }
 
+        if let Some(time_limit) = time_limit {
+            model = model.set_time_limit(time_limit.as_secs_f64());
+        }
+
         let solution = model.solve()?;
+        let values: Vec<f64> = vertex_variables.iter().map(|var| solution.value(*var)).collect();
 
-        let mut max_cut: Vec<Edge> = Vec::new();
-        for (var, edge) in edge_variables.iter().zip(self.edges.iter()) {
-            if solution.value(*var) == 1. {
-                max_cut.push(*edge);
-            }
+        if values.iter().any(|value| (value - value.round()).abs() > INTEGRALITY_TOLERANCE) {
+            return Ok(None);
         }
 
-        Ok(max_cut)
+        let sides: Vec<bool> = values.iter().map(|value| *value > 0.5).collect();
+        Ok(Some(self.graph.cut(&sides)))
     }
 }
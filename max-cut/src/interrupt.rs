@@ -0,0 +1,47 @@
+//! Stopping the solvers on SIGINT and SIGTERM.
+//!
+//! The first signal sets a cancel flag that is handed to the solvers with
+//! [`max_cut::control::RunControl::with_cancel`], so they return the best cut
+//! found so far. A second signal terminates the program right away.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// Exit code after reporting the best cut found before an interruption.
+pub const EXIT_INTERRUPTED: i32 = 3;
+
+static CANCEL: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+/// Installs the signal handlers and returns the flag they set.
+pub fn install() -> Arc<AtomicBool> {
+    let cancel = CANCEL.get_or_init(|| Arc::new(AtomicBool::new(false)));
+
+    #[cfg(unix)]
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        // SAFETY: `handle` only touches atomics and calls `signal`, which are async signal safe
+        unsafe {
+            libc::signal(signal, handle as extern "C" fn(libc::c_int) as libc::sighandler_t);
+        }
+    }
+
+    Arc::clone(cancel)
+}
+
+/// Whether a signal arrived since [`install`].
+pub fn interrupted() -> bool {
+    CANCEL
+        .get()
+        .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+}
+
+#[cfg(unix)]
+extern "C" fn handle(signal: libc::c_int) {
+    if let Some(cancel) = CANCEL.get() {
+        cancel.store(true, Ordering::Relaxed);
+    }
+
+    // The next signal of this kind terminates
+    unsafe {
+        libc::signal(signal, libc::SIG_DFL);
+    }
+}
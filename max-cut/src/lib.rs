@@ -1,6 +1,26 @@
 pub mod graph;
 pub mod graph_parser;
+pub mod graph_writer;
 pub mod approx;
+pub mod best_known;
 pub mod ilp;
 pub mod quad_matrix_bool;
 pub mod heuristic;
+pub mod bipartite;
+pub mod brute_force;
+pub mod certificate;
+pub mod control;
+pub mod decompose;
+pub mod generate;
+pub mod multistart;
+pub mod planar;
+pub mod quality;
+pub mod reduce;
+pub mod solver;
+pub mod spectral;
+pub mod stats;
+pub mod treewidth;
+pub mod verify;
+pub mod viz;
+mod matching;
+mod parallel;
@@ -1,148 +1,455 @@
 mod args;
+mod batch;
+mod interrupt;
+mod output;
+mod progress;
 use std::{
-    fs, process,
-    time::{Instant, Duration}, sync::Arc, thread,
+    fs, path::Path, process,
+    time::{Instant, Duration},
+    sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}},
 };
 
-use args::Args;
-use max_cut::{approx, graph_parser, heuristic, ilp};
+use args::{AlgorithmArgs, Args, BatchArgs, BenchArgs, Command, Model, Preprocessing, ProgressArgs, QualityArgs, SolveArgs};
+use output::{Instance, Outcome, Output, OutputFormat};
+use progress::Progress;
+use max_cut::{best_known::Registry, bipartite, certificate::Certificate, control::RunControl, decompose, generate, graph::{Edge, Graph}, graph_parser, graph_writer, quality, reduce, solver::Algorithm, stats, viz};
 
-use clap::Parser;
+use clap::{error::ErrorKind, CommandFactory, Parser};
 
 fn main() {
     let args = Args::parse();
 
-    let input_graph = fs::read_to_string(&args.file).unwrap_or_else(|err| {
-        eprintln!("could not open \'{}\': {err}", args.file.to_str().unwrap());
-        process::exit(1);
-    });
+    match &args.command {
+        Command::Solve(args) => solve_command(args),
+        Command::Bench(args) => bench_command(args),
+        Command::Batch(args) => batch_command(args),
+        Command::Quality(args) => quality_command(args),
+        Command::Convert { input, output } => write_graph(Some(output), &read_graph(input)),
+        Command::Generate { model } => generate_command(model),
+        Command::Stats { file } => print!("{}", stats::graph_stats(&read_graph(file))),
+        Command::Verify { file, solution } => verify(file, solution),
+    }
+}
 
-    let graph = Arc::new(if args.file.extension().unwrap() == "gr" {
-        graph_parser::parse_pace_graph(&input_graph).unwrap_or_else(|err| {
-            eprintln!("parse error: \n\t{err}");
-            process::exit(1);
-        })
-    } else {
-        graph_parser::parse_rudy(&input_graph).unwrap_or_else(|err| {
-            eprintln!("parse error: \n\t{err}");
-            process::exit(1);
-        })
-    });
+fn solve_command(args: &SolveArgs) {
+    let algorithms = algorithms(&args.algorithms);
+
+    if algorithms.len() > 1 && (args.solution.is_some() || args.dot.is_some() || args.svg.is_some()) {
+        usage_error(
+            ErrorKind::ArgumentConflict,
+            "'--solution', '--dot' and '--svg' can only be used with a single '--algorithm'",
+        );
+    }
+
+    let registry = read_registry(args.best_known.as_deref());
+    let instance = read_instance(&args.file, registry.as_ref());
+    let mut output = open_output(args.output.as_deref(), args.output_format, false);
 
-    if !args.bench {
+    if args.output_format == OutputFormat::Text && args.output.is_none() {
         println!("parsed \'{}\'", args.file.to_str().unwrap());
     }
 
-    if args.ilp >= 0{
-        let ilp = ilp::MaxCutIlp::new(&graph);
+    let progress = open_progress(&args.progress);
+    let cancel = interrupt::install();
 
-        let file = args.file.to_str().unwrap().to_owned();
+    for algorithm in algorithms {
+        let outcome = run(&instance, algorithm, &args.preprocessing, args.algorithms.timeout, &cancel, progress.as_ref());
+        let title = if outcome.interrupted {
+            "Best cut before the interruption"
+        } else if outcome.exact {
+            "Maximum cut"
+        } else {
+            "Approximated maximum cut"
+        };
+
+        write_solution(args, &instance.graph, &outcome);
+        write_drawings(args, &instance.graph, &outcome.cut);
+        report(&mut output, &instance, &outcome, title);
+        exit_if_interrupted();
+    }
+}
+
+fn bench_command(args: &BenchArgs) {
+    let algorithms = algorithms(&args.algorithms);
+    let registry = read_registry(args.best_known.as_deref());
+    let mut output = open_output(args.output.as_deref(), args.output_format, true);
+    let progress = open_progress(&args.progress);
+    let cancel = interrupt::install();
 
-        let timeout = Instant::now();
-        if args.ilp > 0 {
-        thread::spawn(move || {
-            while timeout.elapsed() <= Duration::from_secs(args.ilp.try_into().unwrap()) {}
-            println!("{}, timeout", file);
-            process::exit(2);
-        });
+    for file in &args.files {
+        let instance = read_instance(file, registry.as_ref());
 
+        for &algorithm in &algorithms {
+            let outcome = run(&instance, algorithm, &args.preprocessing, args.algorithms.timeout, &cancel, progress.as_ref());
+            report(&mut output, &instance, &outcome, "Cut");
+            exit_if_interrupted();
         }
-        let start = Instant::now();
+    }
+}
 
-        let exact = ilp.solve().unwrap_or_else(|err| {
-            eprintln!("ilp error: {err}");
-            process::exit(1);
-        });
+fn batch_command(args: &BatchArgs) {
+    let algorithms = algorithms(&args.algorithms);
 
-        let end = start.elapsed();
+    batch::batch(args, &algorithms).unwrap_or_else(|err| {
+        eprintln!("batch error: {err}");
+        process::exit(1);
+    });
 
-        if args.bench {
-            let solution_size = exact.len();
+    if interrupt::interrupted() {
+        eprintln!("interrupted, the unfinished runs are not recorded");
+        process::exit(interrupt::EXIT_INTERRUPTED);
+    }
+}
 
-            println!(
-                "{}, {}, {}, {}, {}",
-                args.file.to_str().unwrap(),
-                graph.size(),
-                graph.edge_size(),
-                solution_size,
-                end.as_millis(),
-            );
-        } else {
-            println!(
-                "Maximum cut for \'{}\':\n\n{:?}",
-                args.file.to_str().unwrap(),
-                exact
-            );
+fn quality_command(args: &QualityArgs) {
+    let algorithms = algorithms(&args.algorithms);
+
+    if args.algorithms.timeout.is_some() {
+        usage_error(ErrorKind::ArgumentConflict, "'--timeout' can't be used with 'quality'");
+    }
+
+    if args.target.is_some() && args.files.len() > 1 {
+        usage_error(
+            ErrorKind::ArgumentConflict,
+            "'--target' can only be used with a single graph",
+        );
+    }
+
+    let registry = read_registry(args.best_known.as_deref());
+    let seeds = args.first_seed..args.first_seed.saturating_add(args.seeds);
+    let mut rows = vec![];
+
+    for file in &args.files {
+        let instance = read_instance(file, registry.as_ref());
+        let target = args
+            .target
+            .or(instance.best_known.map(|best_known| best_known.value));
+        let summaries = quality::compare(&instance.graph, &algorithms, seeds.clone(), target)
+            .unwrap_or_else(|err| {
+                eprintln!("{} error: {err}", file.to_str().unwrap());
+                process::exit(1);
+            });
+
+        rows.extend(
+            summaries
+                .into_iter()
+                .map(|summary| (file.to_str().unwrap().to_owned(), summary)),
+        );
+    }
+
+    if args.csv {
+        print!("{}", output::quality_csv(&rows));
+    } else {
+        print!("{}", quality::table(&rows));
+    }
+}
+
+fn generate_command(model: &Model) {
+    let (graph, target) = match model {
+        Model::Gnp {
+            vertices,
+            probability,
+            target,
+        } => {
+            if !(0.0..=1.0).contains(probability) {
+                usage_error(ErrorKind::InvalidValue, "'<PROBABILITY>' has to be between 0 and 1");
+            }
+
+            (generate::gnp(*vertices, *probability, target.seed), target)
         }
+        Model::Gnm {
+            vertices,
+            edges,
+            target,
+        } => (generate::gnm(*vertices, *edges, target.seed), target),
+    };
+
+    write_graph(target.output.as_deref(), &graph);
+}
+
+/// The algorithms chosen in `args`, exits with a usage error if a parameter
+/// is given without its algorithm.
+fn algorithms(args: &AlgorithmArgs) -> Vec<Algorithm> {
+    args.algorithms()
+        .unwrap_or_else(|err| usage_error(ErrorKind::ArgumentConflict, &err))
+}
+
+fn usage_error(kind: ErrorKind, message: &str) -> ! {
+    Args::command().error(kind, message).exit()
+}
+
+/// Exits with [`interrupt::EXIT_INTERRUPTED`] after a signal,
+/// once the cut found before it is reported.
+fn exit_if_interrupted() {
+    if interrupt::interrupted() {
+        eprintln!("interrupted, reported the best cut found so far");
+        process::exit(interrupt::EXIT_INTERRUPTED);
     }
+}
 
-    if args.approx {
-        let start;
-        let cut;
-        let end;
+/// Runs `algorithm` on `instance` until it is done or `cancel` is set and
+/// writes its events to `progress`.
+/// The ILP stops after `timeout` seconds with the best cut found, which is then not exact.
+fn run(
+    instance: &Instance,
+    algorithm: Algorithm,
+    preprocessing: &Preprocessing,
+    timeout: Option<u64>,
+    cancel: &Arc<AtomicBool>,
+    progress: Option<&Progress>,
+) -> Outcome {
+    let mut control = RunControl::new().with_cancel(Arc::clone(cancel));
+    if let (Algorithm::Ilp, Some(timeout)) = (algorithm, timeout) {
+        control = control.with_time_limit(Duration::from_secs(timeout));
+    }
+    if let Some(progress) = progress {
+        control = progress.observe(instance, &algorithm.to_string(), control);
+    }
+    let exact = AtomicBool::new(true);
+    // The widest tree decomposition of all pieces
+    let width = Mutex::new(None);
+    let preprocessed = !preprocessing.name().is_empty();
+    let start = Instant::now();
 
-        if args.improved {
-            start = Instant::now();
-            cut = approx::max_cut_greedy_impr(&graph);
-            end = start.elapsed();
+    let cut = solve(&instance.graph, preprocessing, |g| {
+        // Blocks and kernels get controls of their own, so that only the
+        // values of the whole graph are reported
+        let solution = if preprocessed {
+            let mut part = RunControl::new().with_cancel(Arc::clone(cancel));
+            if let Some(remaining) = control.remaining() {
+                part = part.with_time_limit(remaining);
+            }
+            algorithm.solve_controlled(g, None, &part)?
         } else {
-            start = Instant::now();
-            cut = approx::max_cut_greedy(&graph);
-            end = start.elapsed();
+            algorithm.solve_controlled(g, None, &control)?
+        };
+        exact.fetch_and(solution.exact, Ordering::Relaxed);
+        if let Some(piece) = solution.width {
+            let mut width = width.lock().unwrap();
+            *width = Some(width.map_or(piece, |width: usize| width.max(piece)));
         }
+        Ok(solution.cut)
+    })
+    .unwrap_or_else(|err: max_cut::solver::SolverError| {
+        eprintln!("{algorithm} error: {err}");
+        process::exit(1);
+    });
 
-        if args.bench {
-            println!(
-                "{}, {}, {}, {}, {}",
-                args.file.to_str().unwrap(),
-                graph.size(),
-                graph.edge_size(),
-                cut.len(),
-                end.as_millis(),
-            );
-        } else {
-            println!(
-                "Appriximated maximum cut for \'{}\': \n\n{:?}",
-                args.file.to_str().unwrap(),
-                cut
-            )
+    let time = start.elapsed();
+    let exact = exact.into_inner();
+
+    if preprocessed {
+        control.improve(&cut);
+        if exact {
+            control.bound(cut.len());
         }
     }
 
-    if args.heuristic {
-        let start;
-        let cut;
-        let end;
+    let outcome = Outcome {
+        algorithm,
+        cut,
+        exact,
+        width: width.into_inner().unwrap(),
+        interrupted: cancel.load(Ordering::Relaxed),
+        time,
+    };
+
+    if let Some(progress) = progress {
+        progress.finish(instance, &outcome, control.elapsed());
+    }
 
-        if args.improved {
-            start = Instant::now();
-            cut = heuristic::rand_approx_impr(Arc::clone(&graph)).unwrap();
-            end = start.elapsed();
-        } else if args.heuristic_parallel {
-            start = Instant::now();
-            cut = heuristic::rand_aprox_parallel(&graph).unwrap();
-            end = start.elapsed();
+    outcome
+}
+
+fn open_progress(args: &ProgressArgs) -> Option<Progress> {
+    args.progress?;
+
+    Some(Progress::new(args.progress_file.as_deref()).unwrap_or_else(|err| {
+        eprintln!("could not open progress file: {err}");
+        process::exit(1);
+    }))
+}
+
+fn open_output(path: Option<&Path>, format: OutputFormat, bench: bool) -> Output {
+    Output::new(path, format, bench).unwrap_or_else(|err| {
+        eprintln!("could not open output: {err}");
+        process::exit(1);
+    })
+}
+
+/// Writes `outcome` to `output` and flags cuts beating the best known value on stderr.
+fn report(output: &mut Output, instance: &Instance, outcome: &Outcome, title: &str) {
+    output.report(instance, outcome, title).unwrap_or_else(|err| {
+        eprintln!("could not write output: {err}");
+        process::exit(1);
+    });
+
+    if let (true, Some(best_known)) = (output::is_record(instance, outcome), instance.best_known) {
+        let known = if best_known.optimal {
+            "the optimum"
+        } else {
+            "the best known value"
+        };
+
+        eprintln!(
+            "new record for \'{}\': {} {} beats {known} {}",
+            instance.file.to_str().unwrap(),
+            outcome.algorithm,
+            outcome.cut.len(),
+            best_known.value
+        );
+    }
+}
+
+/// Runs `solver` on `graph`, on its kernel if `--reduce` is set,
+/// block by block if `--decompose` is set and only on the non bipartite
+/// components if `--bipartite` is set.
+fn solve<F, E>(graph: &Graph, preprocessing: &Preprocessing, solver: F) -> Result<Vec<Edge>, E>
+where
+    F: Fn(&Graph) -> Result<Vec<Edge>, E> + Sync,
+    E: Send,
+{
+    let solver = |g: &Graph| {
+        if preprocessing.bipartite {
+            bipartite::solve(g, &solver)
         } else {
-            start = Instant::now();
-            cut = heuristic::rand_aprox(&graph);
-            end = start.elapsed();
+            solver(g)
         }
+    };
 
-        if args.bench {
-            println!(
-                "{}, {}, {}, {}, {}",
-                args.file.to_str().unwrap(),
-                graph.size(),
-                graph.edge_size(),
-                cut.len(),
-                end.as_millis(),
-            );
+    let solver = |g: &Graph| {
+        if preprocessing.decompose {
+            decompose::solve_blocks(g, solver)
         } else {
-            println!(
-                "Appriximated maximum cut for \'{}\': \n\n{:?}",
-                args.file.to_str().unwrap(),
-                cut
-            )
+            solver(g)
+        }
+    };
+
+    if preprocessing.reduce {
+        reduce::solve_reduced(graph, solver)
+    } else {
+        solver(graph)
+    }
+}
+
+/// Writes the solution file for `outcome` if `--solution` is set.
+/// The bound is the cut itself if it is exact, else the upper bound of [`stats::graph_stats`].
+fn write_solution(args: &SolveArgs, graph: &Graph, outcome: &Outcome) {
+    let Some(path) = &args.solution else {
+        return;
+    };
+
+    let mut certificate = Certificate::new(graph, &outcome.cut).expect("solver returned an invalid cut");
+    certificate.algorithm = Some(outcome.algorithm.to_string());
+    certificate.bound = Some(if outcome.exact {
+        outcome.cut.len()
+    } else {
+        stats::graph_stats(graph).upper_bound
+    });
+
+    write_file(path, &certificate.to_string());
+}
+
+/// Writes the drawings of `cut` requested by `--dot` and `--svg`.
+fn write_drawings(args: &SolveArgs, graph: &Graph, cut: &[Edge]) {
+    if let Some(path) = &args.dot {
+        write_file(path, &viz::to_dot(graph, cut).expect("solver returned an invalid cut"));
+    }
+
+    if let Some(path) = &args.svg {
+        write_file(path, &viz::to_svg(graph, cut, 0).expect("solver returned an invalid cut"));
+    }
+}
+
+/// Checks the solution file `solution` against the graph `file`.
+/// Exits with code 1 if any claim of the solution doesn't hold.
+fn verify(file: &Path, solution: &Path) {
+    let graph = read_graph(file);
+    let raw = fs::read_to_string(solution).unwrap_or_else(|err| {
+        eprintln!("could not open \'{}\': {err}", solution.to_str().unwrap());
+        process::exit(1);
+    });
+
+    let certificate = Certificate::parse(&raw).unwrap_or_else(|err| {
+        eprintln!("parse error: \n\t{err}");
+        process::exit(1);
+    });
+
+    match certificate.verify(&graph) {
+        Ok(value) => println!(
+            "verified \'{}\': cut of {value} edges",
+            solution.to_str().unwrap()
+        ),
+        Err(mismatches) => {
+            for mismatch in mismatches {
+                eprintln!("mismatch: {mismatch}");
+            }
+            process::exit(1);
         }
     }
 }
+
+fn read_instance<'a>(file: &'a Path, registry: Option<&Registry>) -> Instance<'a> {
+    let start = Instant::now();
+    let graph = read_graph(file);
+    let parse_time = start.elapsed();
+
+    Instance {
+        file,
+        best_known: registry.and_then(|registry| registry.lookup(file, &graph)),
+        graph,
+        parse_time,
+    }
+}
+
+fn read_registry(file: Option<&Path>) -> Option<Registry> {
+    let file = file?;
+    let raw = fs::read_to_string(file).unwrap_or_else(|err| {
+        eprintln!("could not open \'{}\': {err}", file.to_str().unwrap());
+        process::exit(1);
+    });
+
+    Some(Registry::parse(&raw).unwrap_or_else(|err| {
+        eprintln!("parse error: \n\t{err}");
+        process::exit(1);
+    }))
+}
+
+fn read_graph(file: &Path) -> Graph {
+    let input_graph = fs::read_to_string(file).unwrap_or_else(|err| {
+        eprintln!("could not open \'{}\': {err}", file.to_str().unwrap());
+        process::exit(1);
+    });
+
+    if is_pace(file) {
+        graph_parser::parse_pace_graph(&input_graph).unwrap_or_else(|err| {
+            eprintln!("parse error: \n\t{err}");
+            process::exit(1);
+        })
+    } else {
+        graph_parser::parse_rudy(&input_graph).unwrap_or_else(|err| {
+            eprintln!("parse error: \n\t{err}");
+            process::exit(1);
+        })
+    }
+}
+
+/// Writes `graph` to `file` in the format given by its extension, or as rudy to stdout.
+fn write_graph(file: Option<&Path>, graph: &Graph) {
+    match file {
+        Some(file) if is_pace(file) => write_file(file, &graph_writer::write_pace_graph(graph)),
+        Some(file) => write_file(file, &graph_writer::write_rudy(graph)),
+        None => print!("{}", graph_writer::write_rudy(graph)),
+    }
+}
+
+fn write_file(path: &Path, contents: &str) {
+    fs::write(path, contents).unwrap_or_else(|err| {
+        eprintln!("could not write \'{}\': {err}", path.to_str().unwrap());
+        process::exit(1);
+    });
+}
+
+fn is_pace(file: &Path) -> bool {
+    file.extension().is_some_and(|extension| extension == "gr")
+}
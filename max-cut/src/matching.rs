@@ -0,0 +1,726 @@
+//! Maximum weight matching in general graphs.
+//!
+//! Edmonds' blossom algorithm with dual variables in `O(n^3)`, following the
+//! well known implementation by Joris van Rantwijk. All weights are doubled
+//! internally so that every dual variable stays integral.
+
+const NONE: usize = usize::MAX;
+
+/// Computes a maximum weight matching of the graph given by `edges`
+/// as `(u, v, weight)` on the vertices `0..n`. If `max_cardinality` is set
+/// only maximum cardinality matchings are considered.
+/// Returns the mate of every vertex.
+pub fn max_weight_matching(
+    n: usize,
+    edges: &[(usize, usize, i64)],
+    max_cardinality: bool,
+) -> Vec<Option<usize>> {
+    if edges.is_empty() {
+        return vec![None; n];
+    }
+
+    let mut matching = Matching::new(n, edges);
+    matching.solve(max_cardinality);
+
+    matching
+        .mate
+        .iter()
+        .map(|p| (*p != NONE).then(|| matching.endpoint[*p]))
+        .collect()
+}
+
+struct Matching {
+    n: usize,
+    edges: Vec<(usize, usize, i64)>,
+    /// `endpoint[p]` is the vertex at end `p % 2` of edge `p / 2`
+    endpoint: Vec<usize>,
+    /// Remote ends of the edges of every vertex
+    neighbend: Vec<Vec<usize>>,
+    /// The remote end of the matched edge of every vertex
+    mate: Vec<usize>,
+    /// 0 free, 1 S, 2 T, 5 marked while scanning, for vertices and blossoms
+    label: Vec<u8>,
+    labelend: Vec<usize>,
+    inblossom: Vec<usize>,
+    blossomparent: Vec<usize>,
+    blossomchilds: Vec<Vec<usize>>,
+    blossombase: Vec<usize>,
+    blossomendps: Vec<Vec<usize>>,
+    bestedge: Vec<usize>,
+    blossombestedges: Vec<Option<Vec<usize>>>,
+    unusedblossoms: Vec<usize>,
+    dualvar: Vec<i64>,
+    allowedge: Vec<bool>,
+    queue: Vec<usize>,
+}
+
+impl Matching {
+    fn new(n: usize, edges: &[(usize, usize, i64)]) -> Matching {
+        let edges: Vec<(usize, usize, i64)> =
+            edges.iter().map(|(u, v, w)| (*u, *v, 2 * w)).collect();
+        let max_weight = edges.iter().map(|e| e.2).max().unwrap_or(0).max(0);
+
+        let endpoint = (0..2 * edges.len())
+            .map(|p| if p % 2 == 0 { edges[p / 2].0 } else { edges[p / 2].1 })
+            .collect();
+
+        let mut neighbend = vec![vec![]; n];
+        for (k, (i, j, _)) in edges.iter().enumerate() {
+            neighbend[*i].push(2 * k + 1);
+            neighbend[*j].push(2 * k);
+        }
+
+        let mut dualvar = vec![max_weight; n];
+        dualvar.extend(vec![0; n]);
+
+        let mut blossombase: Vec<usize> = (0..n).collect();
+        blossombase.extend(vec![NONE; n]);
+
+        Matching {
+            n,
+            endpoint,
+            neighbend,
+            mate: vec![NONE; n],
+            label: vec![0; 2 * n],
+            labelend: vec![NONE; 2 * n],
+            inblossom: (0..n).collect(),
+            blossomparent: vec![NONE; 2 * n],
+            blossomchilds: vec![vec![]; 2 * n],
+            blossombase,
+            blossomendps: vec![vec![]; 2 * n],
+            bestedge: vec![NONE; 2 * n],
+            blossombestedges: vec![None; 2 * n],
+            unusedblossoms: (n..2 * n).collect(),
+            dualvar,
+            allowedge: vec![false; edges.len()],
+            queue: vec![],
+            edges,
+        }
+    }
+
+    fn slack(&self, k: usize) -> i64 {
+        let (i, j, w) = self.edges[k];
+        self.dualvar[i] + self.dualvar[j] - 2 * w
+    }
+
+    fn blossom_leaves(&self, b: usize) -> Vec<usize> {
+        let mut leaves = vec![];
+        let mut stack = vec![b];
+
+        while let Some(b) = stack.pop() {
+            if b < self.n {
+                leaves.push(b);
+            } else {
+                stack.extend(self.blossomchilds[b].iter().rev());
+            }
+        }
+
+        leaves
+    }
+
+    fn assign_label(&mut self, w: usize, t: u8, p: usize) {
+        let (mut w, mut t, mut p) = (w, t, p);
+
+        loop {
+            let b = self.inblossom[w];
+            self.label[w] = t;
+            self.label[b] = t;
+            self.labelend[w] = p;
+            self.labelend[b] = p;
+            self.bestedge[w] = NONE;
+            self.bestedge[b] = NONE;
+
+            if t == 1 {
+                let leaves = self.blossom_leaves(b);
+                self.queue.extend(leaves);
+                return;
+            }
+
+            // Label the mate of the base of a T-blossom with S
+            let base = self.blossombase[b];
+            let matched = self.mate[base];
+            w = self.endpoint[matched];
+            t = 1;
+            p = matched ^ 1;
+        }
+    }
+
+    /// Traces back from `v` and `w` to find a new blossom or an augmenting path.
+    /// Returns the base of the blossom or `NONE` for an augmenting path.
+    fn scan_blossom(&mut self, v: usize, w: usize) -> usize {
+        let (mut v, mut w) = (v, w);
+        let mut path = vec![];
+        let mut base = NONE;
+
+        while v != NONE || w != NONE {
+            let mut b = self.inblossom[v];
+
+            if self.label[b] & 4 != 0 {
+                base = self.blossombase[b];
+                break;
+            }
+
+            path.push(b);
+            self.label[b] = 5;
+
+            if self.labelend[b] == NONE {
+                v = NONE;
+            } else {
+                v = self.endpoint[self.labelend[b]];
+                b = self.inblossom[v];
+                v = self.endpoint[self.labelend[b]];
+            }
+
+            if w != NONE {
+                std::mem::swap(&mut v, &mut w);
+            }
+        }
+
+        for b in path {
+            self.label[b] = 1;
+        }
+
+        base
+    }
+
+    fn add_blossom(&mut self, base: usize, k: usize) {
+        let (mut v, mut w, _) = self.edges[k];
+        let bb = self.inblossom[base];
+        let mut bv = self.inblossom[v];
+        let mut bw = self.inblossom[w];
+
+        let b = self.unusedblossoms.pop().unwrap();
+        self.blossombase[b] = base;
+        self.blossomparent[b] = NONE;
+        self.blossomparent[bb] = b;
+
+        let mut path = vec![];
+        let mut endps = vec![];
+
+        while bv != bb {
+            self.blossomparent[bv] = b;
+            path.push(bv);
+            endps.push(self.labelend[bv]);
+            v = self.endpoint[self.labelend[bv]];
+            bv = self.inblossom[v];
+        }
+
+        path.push(bb);
+        path.reverse();
+        endps.reverse();
+        endps.push(2 * k);
+
+        while bw != bb {
+            self.blossomparent[bw] = b;
+            path.push(bw);
+            endps.push(self.labelend[bw] ^ 1);
+            w = self.endpoint[self.labelend[bw]];
+            bw = self.inblossom[w];
+        }
+
+        self.label[b] = 1;
+        self.labelend[b] = self.labelend[bb];
+        self.dualvar[b] = 0;
+        self.blossomchilds[b] = path.clone();
+        self.blossomendps[b] = endps;
+
+        for v in self.blossom_leaves(b) {
+            if self.label[self.inblossom[v]] == 2 {
+                self.queue.push(v);
+            }
+            self.inblossom[v] = b;
+        }
+
+        let mut bestedgeto = vec![NONE; 2 * self.n];
+
+        for bv in path {
+            let nblists: Vec<Vec<usize>> = match self.blossombestedges[bv].take() {
+                Some(list) => vec![list],
+                None => self
+                    .blossom_leaves(bv)
+                    .iter()
+                    .map(|v| self.neighbend[*v].iter().map(|p| p / 2).collect())
+                    .collect(),
+            };
+
+            for nblist in nblists {
+                for k in nblist {
+                    let (mut i, mut j, _) = self.edges[k];
+                    if self.inblossom[j] == b {
+                        std::mem::swap(&mut i, &mut j);
+                    }
+
+                    let bj = self.inblossom[j];
+                    if bj != b
+                        && self.label[bj] == 1
+                        && (bestedgeto[bj] == NONE || self.slack(k) < self.slack(bestedgeto[bj]))
+                    {
+                        bestedgeto[bj] = k;
+                    }
+                }
+            }
+
+            self.bestedge[bv] = NONE;
+        }
+
+        let best: Vec<usize> = bestedgeto.into_iter().filter(|k| *k != NONE).collect();
+        self.bestedge[b] = NONE;
+
+        for k in &best {
+            if self.bestedge[b] == NONE || self.slack(*k) < self.slack(self.bestedge[b]) {
+                self.bestedge[b] = *k;
+            }
+        }
+
+        self.blossombestedges[b] = Some(best);
+    }
+
+    fn expand_blossom(&mut self, b: usize, endstage: bool) {
+        for s in self.blossomchilds[b].clone() {
+            self.blossomparent[s] = NONE;
+
+            if s < self.n {
+                self.inblossom[s] = s;
+            } else if endstage && self.dualvar[s] == 0 {
+                self.expand_blossom(s, endstage);
+            } else {
+                for v in self.blossom_leaves(s) {
+                    self.inblossom[v] = s;
+                }
+            }
+        }
+
+        if !endstage && self.label[b] == 2 {
+            let childs = self.blossomchilds[b].clone();
+            let endps = self.blossomendps[b].clone();
+            let len = childs.len() as isize;
+            let at = |j: isize| j.rem_euclid(len) as usize;
+
+            let entrychild = self.inblossom[self.endpoint[self.labelend[b] ^ 1]];
+            let mut j = childs.iter().position(|c| *c == entrychild).unwrap() as isize;
+
+            let (jstep, endptrick) = if j & 1 != 0 {
+                j -= len;
+                (1, 0)
+            } else {
+                (-1, 1)
+            };
+
+            let mut p = self.labelend[b];
+
+            while j != 0 {
+                self.label[self.endpoint[p ^ 1]] = 0;
+                self.label[self.endpoint[endps[at(j - endptrick)] ^ endptrick as usize ^ 1]] = 0;
+                self.assign_label(self.endpoint[p ^ 1], 2, p);
+                self.allowedge[endps[at(j - endptrick)] / 2] = true;
+                j += jstep;
+                p = endps[at(j - endptrick)] ^ endptrick as usize;
+                self.allowedge[p / 2] = true;
+                j += jstep;
+            }
+
+            let bv = childs[at(j)];
+            self.label[self.endpoint[p ^ 1]] = 2;
+            self.label[bv] = 2;
+            self.labelend[self.endpoint[p ^ 1]] = p;
+            self.labelend[bv] = p;
+            self.bestedge[bv] = NONE;
+            j += jstep;
+
+            while childs[at(j)] != entrychild {
+                let bv = childs[at(j)];
+
+                if self.label[bv] == 1 {
+                    j += jstep;
+                    continue;
+                }
+
+                if let Some(v) = self
+                    .blossom_leaves(bv)
+                    .into_iter()
+                    .find(|v| self.label[*v] != 0)
+                {
+                    self.label[v] = 0;
+                    self.label[self.endpoint[self.mate[self.blossombase[bv]]]] = 0;
+                    self.assign_label(v, 2, self.labelend[v]);
+                }
+
+                j += jstep;
+            }
+        }
+
+        self.label[b] = 0;
+        self.labelend[b] = NONE;
+        self.blossomchilds[b] = vec![];
+        self.blossomendps[b] = vec![];
+        self.blossombase[b] = NONE;
+        self.blossombestedges[b] = None;
+        self.bestedge[b] = NONE;
+        self.unusedblossoms.push(b);
+    }
+
+    fn augment_blossom(&mut self, b: usize, v: usize) {
+        let mut t = v;
+        while self.blossomparent[t] != b {
+            t = self.blossomparent[t];
+        }
+
+        if t >= self.n {
+            self.augment_blossom(t, v);
+        }
+
+        let len = self.blossomchilds[b].len() as isize;
+        let at = |j: isize| j.rem_euclid(len) as usize;
+        let i = self.blossomchilds[b].iter().position(|c| *c == t).unwrap();
+        let mut j = i as isize;
+
+        let (jstep, endptrick) = if j & 1 != 0 {
+            j -= len;
+            (1, 0)
+        } else {
+            (-1, 1)
+        };
+
+        while j != 0 {
+            j += jstep;
+            let t = self.blossomchilds[b][at(j)];
+            let p = self.blossomendps[b][at(j - endptrick)] ^ endptrick as usize;
+
+            if t >= self.n {
+                self.augment_blossom(t, self.endpoint[p]);
+            }
+
+            j += jstep;
+            let t = self.blossomchilds[b][at(j)];
+
+            if t >= self.n {
+                self.augment_blossom(t, self.endpoint[p ^ 1]);
+            }
+
+            self.mate[self.endpoint[p]] = p ^ 1;
+            self.mate[self.endpoint[p ^ 1]] = p;
+        }
+
+        self.blossomchilds[b].rotate_left(i);
+        self.blossomendps[b].rotate_left(i);
+        self.blossombase[b] = self.blossombase[self.blossomchilds[b][0]];
+    }
+
+    fn augment_matching(&mut self, k: usize) {
+        let (v, w, _) = self.edges[k];
+
+        for (s, p) in [(v, 2 * k + 1), (w, 2 * k)] {
+            let (mut s, mut p) = (s, p);
+
+            loop {
+                let bs = self.inblossom[s];
+
+                if bs >= self.n {
+                    self.augment_blossom(bs, s);
+                }
+
+                self.mate[s] = p;
+
+                if self.labelend[bs] == NONE {
+                    break;
+                }
+
+                let t = self.endpoint[self.labelend[bs]];
+                let bt = self.inblossom[t];
+                s = self.endpoint[self.labelend[bt]];
+                let j = self.endpoint[self.labelend[bt] ^ 1];
+
+                if bt >= self.n {
+                    self.augment_blossom(bt, j);
+                }
+
+                self.mate[j] = self.labelend[bt];
+                p = self.labelend[bt] ^ 1;
+            }
+        }
+    }
+
+    fn solve(&mut self, max_cardinality: bool) {
+        let n = self.n;
+
+        for _ in 0..n {
+            self.label.iter_mut().for_each(|l| *l = 0);
+            self.bestedge.iter_mut().for_each(|e| *e = NONE);
+            self.blossombestedges[n..].iter_mut().for_each(|e| *e = None);
+            self.allowedge.iter_mut().for_each(|a| *a = false);
+            self.queue.clear();
+
+            for v in 0..n {
+                if self.mate[v] == NONE && self.label[self.inblossom[v]] == 0 {
+                    self.assign_label(v, 1, NONE);
+                }
+            }
+
+            let mut augmented = false;
+
+            loop {
+                while !augmented {
+                    let Some(v) = self.queue.pop() else {
+                        break;
+                    };
+
+                    for p in self.neighbend[v].clone() {
+                        let k = p / 2;
+                        let w = self.endpoint[p];
+
+                        if self.inblossom[v] == self.inblossom[w] {
+                            continue;
+                        }
+
+                        let mut kslack = 0;
+                        if !self.allowedge[k] {
+                            kslack = self.slack(k);
+                            if kslack <= 0 {
+                                self.allowedge[k] = true;
+                            }
+                        }
+
+                        if self.allowedge[k] {
+                            if self.label[self.inblossom[w]] == 0 {
+                                self.assign_label(w, 2, p ^ 1);
+                            } else if self.label[self.inblossom[w]] == 1 {
+                                let base = self.scan_blossom(v, w);
+                                if base != NONE {
+                                    self.add_blossom(base, k);
+                                } else {
+                                    self.augment_matching(k);
+                                    augmented = true;
+                                    break;
+                                }
+                            } else if self.label[w] == 0 {
+                                self.label[w] = 2;
+                                self.labelend[w] = p ^ 1;
+                            }
+                        } else if self.label[self.inblossom[w]] == 1 {
+                            let b = self.inblossom[v];
+                            if self.bestedge[b] == NONE || kslack < self.slack(self.bestedge[b]) {
+                                self.bestedge[b] = k;
+                            }
+                        } else if self.label[w] == 0
+                            && (self.bestedge[w] == NONE || kslack < self.slack(self.bestedge[w]))
+                        {
+                            self.bestedge[w] = k;
+                        }
+                    }
+                }
+
+                if augmented {
+                    break;
+                }
+
+                // No augmenting path with tight edges, update the duals
+                let mut deltatype = 0;
+                let mut delta = 0;
+                let mut deltaedge = NONE;
+                let mut deltablossom = NONE;
+
+                if !max_cardinality {
+                    deltatype = 1;
+                    delta = *self.dualvar[..n].iter().min().unwrap();
+                }
+
+                for v in 0..n {
+                    if self.label[self.inblossom[v]] == 0 && self.bestedge[v] != NONE {
+                        let d = self.slack(self.bestedge[v]);
+                        if deltatype == 0 || d < delta {
+                            delta = d;
+                            deltatype = 2;
+                            deltaedge = self.bestedge[v];
+                        }
+                    }
+                }
+
+                for b in 0..2 * n {
+                    if self.blossomparent[b] == NONE
+                        && self.label[b] == 1
+                        && self.bestedge[b] != NONE
+                    {
+                        let d = self.slack(self.bestedge[b]) / 2;
+                        if deltatype == 0 || d < delta {
+                            delta = d;
+                            deltatype = 3;
+                            deltaedge = self.bestedge[b];
+                        }
+                    }
+                }
+
+                for b in n..2 * n {
+                    if self.blossombase[b] != NONE
+                        && self.blossomparent[b] == NONE
+                        && self.label[b] == 2
+                        && (deltatype == 0 || self.dualvar[b] < delta)
+                    {
+                        delta = self.dualvar[b];
+                        deltatype = 4;
+                        deltablossom = b;
+                    }
+                }
+
+                if deltatype == 0 {
+                    // Only possible with max_cardinality, no further improvement
+                    deltatype = 1;
+                    delta = (*self.dualvar[..n].iter().min().unwrap()).max(0);
+                }
+
+                for v in 0..n {
+                    match self.label[self.inblossom[v]] {
+                        1 => self.dualvar[v] -= delta,
+                        2 => self.dualvar[v] += delta,
+                        _ => {}
+                    }
+                }
+
+                for b in n..2 * n {
+                    if self.blossombase[b] != NONE && self.blossomparent[b] == NONE {
+                        match self.label[b] {
+                            1 => self.dualvar[b] += delta,
+                            2 => self.dualvar[b] -= delta,
+                            _ => {}
+                        }
+                    }
+                }
+
+                match deltatype {
+                    1 => break,
+                    2 => {
+                        self.allowedge[deltaedge] = true;
+                        let (mut i, j, _) = self.edges[deltaedge];
+                        if self.label[self.inblossom[i]] == 0 {
+                            i = j;
+                        }
+                        self.queue.push(i);
+                    }
+                    3 => {
+                        self.allowedge[deltaedge] = true;
+                        let (i, _, _) = self.edges[deltaedge];
+                        self.queue.push(i);
+                    }
+                    _ => self.expand_blossom(deltablossom, false),
+                }
+            }
+
+            if !augmented {
+                break;
+            }
+
+            for b in n..2 * n {
+                if self.blossomparent[b] == NONE
+                    && self.blossombase[b] != NONE
+                    && self.label[b] == 1
+                    && self.dualvar[b] == 0
+                {
+                    self.expand_blossom(b, true);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    /// Best (cardinality, weight) over all matchings
+    fn exhaustive(n: usize, edges: &[(usize, usize, i64)], max_cardinality: bool) -> (usize, i64) {
+        let mut best = (0, 0);
+
+        for mask in 0..1u32 << edges.len() {
+            let mut used = vec![false; n];
+            let mut value = (0, 0);
+            let mut valid = true;
+
+            for (k, (u, v, w)) in edges.iter().enumerate() {
+                if mask & (1 << k) != 0 {
+                    if used[*u] || used[*v] {
+                        valid = false;
+                        break;
+                    }
+                    used[*u] = true;
+                    used[*v] = true;
+                    value = (value.0 + 1, value.1 + w);
+                }
+            }
+
+            if valid {
+                let better = if max_cardinality {
+                    value > best
+                } else {
+                    value.1 > best.1
+                };
+                if better {
+                    best = value;
+                }
+            }
+        }
+
+        best
+    }
+
+    fn evaluate(edges: &[(usize, usize, i64)], mate: &[Option<usize>]) -> (usize, i64) {
+        let mut value = (0, 0);
+
+        for (u, v, w) in edges {
+            if mate[*u] == Some(*v) {
+                value = (value.0 + 1, value.1 + w);
+            }
+        }
+
+        value
+    }
+
+    #[test]
+    fn simple() {
+        let edges = [(0, 1, 5), (1, 2, 11), (2, 3, 5)];
+
+        assert_eq!(
+            max_weight_matching(4, &edges, false),
+            vec![None, Some(2), Some(1), None]
+        );
+        assert_eq!(
+            max_weight_matching(4, &edges, true),
+            vec![Some(1), Some(0), Some(3), Some(2)]
+        );
+    }
+
+    #[test]
+    fn random_matches_exhaustive() {
+        let mut rng = SmallRng::seed_from_u64(30);
+
+        for round in 0..300 {
+            let n = rng.gen_range(2..9);
+            let mut edges = vec![];
+
+            for u in 0..n {
+                for v in u + 1..n {
+                    if edges.len() < 14 && rng.gen_bool(0.5) {
+                        edges.push((u, v, rng.gen_range(-3..20)));
+                    }
+                }
+            }
+
+            let max_cardinality = round % 2 == 0;
+            let mate = max_weight_matching(n, &edges, max_cardinality);
+
+            for (v, m) in mate.iter().enumerate() {
+                if let Some(m) = m {
+                    assert_eq!(mate[*m], Some(v));
+                }
+            }
+
+            let found = evaluate(&edges, &mate);
+            let best = exhaustive(n, &edges, max_cardinality);
+
+            if max_cardinality {
+                assert_eq!(found, best);
+            } else {
+                assert_eq!(found.1, best.1);
+            }
+        }
+    }
+}
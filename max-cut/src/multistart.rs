@@ -0,0 +1,308 @@
+//! Parallel multi-start search.
+//!
+//! A [`MultiStart`] builds many bipartitions with a randomized construction,
+//! optionally improves each of them by a local search and keeps the best one.
+//! The starts run on a fixed set of worker threads for the whole search. Every
+//! start draws its random choices from its own generator derived from the seed
+//! and its index, so a search limited by the number of starts finds the same
+//! cut on any number of cores.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::control::{RunControl, Stop};
+use crate::graph::{Edge, Graph};
+use crate::parallel;
+
+/// Builds the sides of all vertices from a generator.
+pub type Construction = dyn Fn(&Graph, &mut SmallRng) -> Vec<bool> + Sync;
+
+/// Improves the sides of all vertices in place.
+pub type LocalSearch = dyn Fn(&Graph, &mut [bool]) + Sync;
+
+/// Stopping criteria of a multi-start search besides the time limit, target
+/// and cancellation of its [`RunControl`], all unlimited by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MultiStart {
+    starts: Option<u64>,
+    stagnation: Option<u64>,
+    seed: Option<u64>,
+}
+
+/// Why a multi-start search stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// All starts are done
+    Starts,
+    /// The last starts didn't improve the best cut
+    Stagnation,
+    /// The [`RunControl`] of the search asked to stop
+    Control(Stop),
+}
+
+/// Statistics of a multi-start search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics {
+    /// Number of finished starts
+    pub starts: u64,
+    /// Number of starts improving the best cut
+    pub improvements: u64,
+    /// Index of the start that found the best cut
+    pub best_start: u64,
+    /// Mean cut value of all starts
+    pub mean: f64,
+    pub time: Duration,
+    /// Time until the best cut was found
+    pub time_to_best: Duration,
+    /// `None` if the search ran without any start
+    pub stop: Option<StopReason>,
+}
+
+/// Best cut of a multi-start search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiStartResult {
+    pub cut: Vec<Edge>,
+    /// Sides of all vertices inducing `cut`
+    pub sides: Vec<bool>,
+    pub statistics: Statistics,
+}
+
+/// State shared by the workers.
+struct Search {
+    value: usize,
+    start: u64,
+    sides: Vec<bool>,
+    finished: u64,
+    improvements: u64,
+    last_improvement: u64,
+    sum: f64,
+    time_to_best: Duration,
+    /// Reason that stops all workers
+    stop: Option<StopReason>,
+    /// Whether a worker ran out of starts
+    exhausted: bool,
+}
+
+impl MultiStart {
+    pub fn new() -> MultiStart {
+        MultiStart::default()
+    }
+
+    /// Stops after `starts` starts.
+    pub fn with_starts(mut self, starts: u64) -> MultiStart {
+        self.starts = Some(starts);
+        self
+    }
+
+    /// Stops after `starts` starts in a row without improving the best cut.
+    pub fn with_stagnation(mut self, starts: u64) -> MultiStart {
+        self.stagnation = Some(starts);
+        self
+    }
+
+    /// Derives the generators of the starts from `seed` instead of entropy.
+    pub fn with_seed(mut self, seed: u64) -> MultiStart {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Runs the search on `graph` with `construction` and `local_search`.
+    /// Every start is an iteration and a restart of `control`, improved cuts
+    /// are reported to it. Of equal cuts the one of the lowest start is kept.
+    pub fn run(
+        &self,
+        graph: &Graph,
+        construction: &Construction,
+        local_search: Option<&LocalSearch>,
+        control: &RunControl,
+    ) -> MultiStartResult {
+        let begin = Instant::now();
+        let seed = self
+            .seed
+            .unwrap_or_else(|| SmallRng::from_entropy().gen());
+        let next = AtomicU64::new(0);
+        let search = Mutex::new(Search {
+            value: 0,
+            start: 0,
+            sides: vec![false; graph.size()],
+            finished: 0,
+            improvements: 0,
+            last_improvement: 0,
+            sum: 0.0,
+            time_to_best: Duration::ZERO,
+            stop: None,
+            exhausted: false,
+        });
+
+        // Why `start` may not begin, `None` if it may
+        let stop = |search: &Search, start: u64| {
+            if search.stop.is_some() {
+                search.stop
+            } else if self
+                .stagnation
+                .is_some_and(|stagnation| search.finished > 0 && search.finished - search.last_improvement >= stagnation)
+            {
+                Some(StopReason::Stagnation)
+            } else if self.starts.is_some_and(|starts| start >= starts) {
+                Some(StopReason::Starts)
+            } else {
+                None
+            }
+        };
+
+        thread::scope(|scope| {
+            for _ in 0..parallel::cores() {
+                scope.spawn(|| loop {
+                    let start = next.fetch_add(1, Ordering::Relaxed);
+
+                    {
+                        let mut search = search.lock().unwrap();
+                        let reason = stop(&search, start).or_else(|| {
+                            (!control.proceed())
+                                .then(|| StopReason::Control(control.stop().unwrap_or(Stop::IterationLimit)))
+                        });
+
+                        match reason {
+                            // Lower starts of the other workers still run
+                            Some(StopReason::Starts) => {
+                                search.exhausted = true;
+                                break;
+                            }
+                            // Stops the other workers too, the first reason counts
+                            Some(reason) => {
+                                search.stop.get_or_insert(reason);
+                                break;
+                            }
+                            None => {}
+                        }
+                    }
+
+                    control.restart();
+                    let mut rand = SmallRng::seed_from_u64(parallel::split_seed(seed, start));
+                    let mut sides = construction(graph, &mut rand);
+                    if let Some(local_search) = local_search {
+                        local_search(graph, &mut sides);
+                    }
+                    let value = cut_value(graph, &sides);
+
+                    let improved = {
+                        let mut search = search.lock().unwrap();
+                        let improved = value > search.value;
+                        search.finished += 1;
+                        search.sum += value as f64;
+
+                        if improved {
+                            search.improvements += 1;
+                            search.last_improvement = search.finished;
+                            search.time_to_best = begin.elapsed();
+                        }
+
+                        if improved || (value == search.value && start < search.start) {
+                            search.value = value;
+                            search.start = start;
+                            search.sides.clone_from(&sides);
+                        }
+
+                        improved
+                    };
+
+                    if improved {
+                        control.improve(&graph.cut(&sides));
+                    }
+                });
+            }
+        });
+
+        let search = search.into_inner().unwrap();
+
+        MultiStartResult {
+            cut: graph.cut(&search.sides),
+            sides: search.sides,
+            statistics: Statistics {
+                starts: search.finished,
+                improvements: search.improvements,
+                best_start: search.start,
+                mean: if search.finished > 0 {
+                    search.sum / search.finished as f64
+                } else {
+                    0.0
+                },
+                time: begin.elapsed(),
+                time_to_best: search.time_to_best,
+                stop: search.stop.or(search.exhausted.then_some(StopReason::Starts)),
+            },
+        }
+    }
+}
+
+/// Sides of every vertex drawn uniformly at random.
+pub fn random_sides(graph: &Graph, rand: &mut SmallRng) -> Vec<bool> {
+    (0..graph.size()).map(|_| rand.gen_bool(1.0 / 2.0)).collect()
+}
+
+fn cut_value(graph: &Graph, sides: &[bool]) -> usize {
+    (0..graph.size())
+        .map(|vertex| {
+            graph
+                .neighbors(vertex)
+                .iter()
+                .filter(|neigh| **neigh > vertex && sides[**neigh] != sides[vertex])
+                .count()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx;
+    use crate::generate;
+
+    #[test]
+    fn starts_are_reproducible() {
+        let graph = generate::gnp(60, 0.2, 46);
+        let search = MultiStart::new().with_starts(40).with_seed(3);
+        let result = search.run(&graph, &random_sides, None, &RunControl::new());
+
+        assert_eq!(result.statistics.starts, 40);
+        assert_eq!(result.statistics.stop, Some(StopReason::Starts));
+        assert!(result.statistics.mean <= result.cut.len() as f64);
+
+        let again = search.run(&graph, &random_sides, None, &RunControl::new());
+        assert_eq!(result.cut, again.cut);
+        assert_eq!(result.statistics.best_start, again.statistics.best_start);
+    }
+
+    #[test]
+    fn stopping_criteria() {
+        let graph = generate::gnp(60, 0.2, 46);
+        let control = RunControl::new().with_target(graph.edge_size().div_ceil(2));
+        let vertices: Vec<usize> = (0..graph.size()).collect();
+        let local_search = move |graph: &Graph, sides: &mut [bool]| approx::local_search(graph, sides, &vertices, usize::MAX);
+        let improved = MultiStart::new().run(&graph, &random_sides, Some(&local_search), &control);
+
+        // A local optimum cuts half of the edges at every vertex
+        assert_eq!(improved.statistics.stop, Some(StopReason::Control(Stop::Target)));
+        assert!(2 * improved.cut.len() >= graph.edge_size());
+
+        let stagnated = MultiStart::new()
+            .with_stagnation(5)
+            .run(&graph, &random_sides, None, &RunControl::new());
+        assert_eq!(stagnated.statistics.stop, Some(StopReason::Stagnation));
+
+        let control = RunControl::new().with_iteration_limit(7);
+        let limited = MultiStart::new().run(&graph, &random_sides, None, &control);
+        assert_eq!(limited.statistics.starts, 7);
+        assert_eq!(limited.statistics.stop, Some(StopReason::Control(Stop::IterationLimit)));
+
+        let control = RunControl::new().with_time_limit(Duration::ZERO);
+        let timed = MultiStart::new().run(&graph, &random_sides, None, &control);
+        assert_eq!(timed.statistics.starts, 0);
+        assert_eq!(timed.statistics.stop, Some(StopReason::Control(Stop::TimeLimit)));
+    }
+}
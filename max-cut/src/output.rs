@@ -0,0 +1,272 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+    time::Duration,
+};
+
+use clap::ValueEnum;
+use max_cut::{
+    best_known::BestKnown,
+    certificate,
+    graph::{Edge, Graph},
+    quality::{self, Summary},
+    solver::Algorithm,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable text, a CSV line without header with '--bench'
+    Text,
+    /// One JSON object per result with metadata and timings
+    Json,
+    /// CSV with a header line
+    Csv,
+    /// The side 0 or 1 of every vertex
+    Partition,
+    /// The cut edges, one per line
+    Edges,
+}
+
+/// A cut found by one of the algorithms.
+pub struct Outcome {
+    pub algorithm: Algorithm,
+    pub cut: Vec<Edge>,
+    /// Whether `cut` is guaranteed to be maximum
+    pub exact: bool,
+    /// Tree decomposition width of 'treewidth', or the width it gave up at,
+    /// the largest of all pieces
+    pub width: Option<usize>,
+    /// Whether the run was stopped by a signal before it was done
+    pub interrupted: bool,
+    pub time: Duration,
+}
+
+/// A parsed input graph.
+pub struct Instance<'a> {
+    pub file: &'a Path,
+    pub graph: Graph,
+    pub parse_time: Duration,
+    /// Best known cut value from the reference file given with '--best-known'
+    pub best_known: Option<BestKnown>,
+}
+
+/// Writes the results in the chosen format to stdout or a file.
+pub struct Output {
+    writer: Box<dyn Write>,
+    format: OutputFormat,
+    bench: bool,
+    header: bool,
+}
+
+impl Output {
+    /// Writes to `output` or to stdout if it isn't given.
+    /// With `bench` the text format is a CSV line without header.
+    pub fn new(output: Option<&Path>, format: OutputFormat, bench: bool) -> io::Result<Output> {
+        let writer: Box<dyn Write> = match output {
+            Some(path) => Box::new(io::BufWriter::new(fs::File::create(path)?)),
+            None => Box::new(io::stdout()),
+        };
+
+        Ok(Output {
+            writer,
+            format,
+            bench,
+            header: false,
+        })
+    }
+
+    /// Writes `outcome` on `instance`, `title` describes it in the text format.
+    pub fn report(&mut self, instance: &Instance, outcome: &Outcome, title: &str) -> io::Result<()> {
+        let name = instance.file.to_str().unwrap();
+        let graph = &instance.graph;
+
+        match self.format {
+            OutputFormat::Text if self.bench => writeln!(
+                self.writer,
+                "{}, {}, {}, {}, {}",
+                name,
+                graph.size(),
+                graph.edge_size(),
+                outcome.cut.len(),
+                outcome.time.as_millis(),
+            )?,
+            OutputFormat::Text => {
+                writeln!(
+                    self.writer,
+                    "{title} for \'{name}\':\n\n{:?}",
+                    outcome.cut
+                )?;
+
+                match outcome.width {
+                    Some(width) if outcome.exact => writeln!(self.writer, "\ntree decomposition width {width}")?,
+                    Some(width) => writeln!(self.writer, "\ntree decomposition too wide, gave up at width {width}")?,
+                    None => {}
+                }
+
+                if let Some(best_known) = instance.best_known {
+                    let optimal = if best_known.optimal { "optimum" } else { "best known value" };
+
+                    writeln!(
+                        self.writer,
+                        "\n{} edges, {optimal} {}, gap {:.3}%",
+                        outcome.cut.len(),
+                        best_known.value,
+                        best_known.gap(outcome.cut.len()) * 100.0
+                    )?;
+                }
+            }
+            OutputFormat::Json => self.json(instance, outcome)?,
+            OutputFormat::Csv => {
+                if !self.header {
+                    writeln!(
+                        self.writer,
+                        "file,algorithm,vertices,edges,cut,exact,time_ms,best_known,gap_percent,record"
+                    )?;
+                    self.header = true;
+                }
+
+                let (best_known, gap) = match instance.best_known {
+                    Some(best_known) => (
+                        best_known.value.to_string(),
+                        format!("{:.3}", best_known.gap(outcome.cut.len()) * 100.0),
+                    ),
+                    None => (String::new(), String::new()),
+                };
+
+                writeln!(
+                    self.writer,
+                    "{},{},{},{},{},{},{:.3},{},{},{}",
+                    csv_field(name),
+                    outcome.algorithm,
+                    graph.size(),
+                    graph.edge_size(),
+                    outcome.cut.len(),
+                    outcome.exact,
+                    millis(outcome.time),
+                    best_known,
+                    gap,
+                    is_record(instance, outcome),
+                )?
+            }
+            OutputFormat::Partition => {
+                let sides: Vec<&str> = partition(graph, outcome)
+                    .iter()
+                    .map(|side| if *side { "1" } else { "0" })
+                    .collect();
+                writeln!(self.writer, "{}", sides.join(" "))?
+            }
+            OutputFormat::Edges => {
+                for edge in &outcome.cut {
+                    writeln!(self.writer, "{} {}", edge.0 + 1, edge.1 + 1)?;
+                }
+            }
+        }
+
+        self.writer.flush()
+    }
+
+    fn json(&mut self, instance: &Instance, outcome: &Outcome) -> io::Result<()> {
+        let graph = &instance.graph;
+        let partition: Vec<&str> = partition(graph, outcome)
+            .iter()
+            .map(|side| if *side { "1" } else { "0" })
+            .collect();
+        let cut: Vec<String> = outcome
+            .cut
+            .iter()
+            .map(|edge| format!("[{},{}]", edge.0 + 1, edge.1 + 1))
+            .collect();
+        let (best_known, gap) = match instance.best_known {
+            Some(best_known) => (
+                best_known.value.to_string(),
+                format!("{:.3}", best_known.gap(outcome.cut.len()) * 100.0),
+            ),
+            None => (String::from("null"), String::from("null")),
+        };
+
+        writeln!(
+            self.writer,
+            "{{\"file\":{},\"instance\":\"{:016x}\",\"algorithm\":\"{}\",\"vertices\":{},\"edges\":{},\
+             \"cut\":{},\"exact\":{},\"width\":{},\"interrupted\":{},\"best_known\":{},\"gap_percent\":{},\"record\":{},\
+             \"timings\":{{\"parse_ms\":{:.3},\"solve_ms\":{:.3}}},\
+             \"partition\":[{}],\"cut_edges\":[{}]}}",
+            json_string(instance.file.to_str().unwrap()),
+            certificate::instance_hash(graph),
+            outcome.algorithm,
+            graph.size(),
+            graph.edge_size(),
+            outcome.cut.len(),
+            outcome.exact,
+            outcome.width.map_or(String::from("null"), |width| width.to_string()),
+            outcome.interrupted,
+            best_known,
+            gap,
+            is_record(instance, outcome),
+            millis(instance.parse_time),
+            millis(outcome.time),
+            partition.join(","),
+            cut.join(","),
+        )
+    }
+}
+
+/// Whether `outcome` beats the best known value of `instance`.
+pub fn is_record(instance: &Instance, outcome: &Outcome) -> bool {
+    instance
+        .best_known
+        .is_some_and(|best_known| best_known.is_record(outcome.cut.len()))
+}
+
+fn partition(graph: &Graph, outcome: &Outcome) -> Vec<bool> {
+    graph
+        .partition(&outcome.cut)
+        .expect("solver returned an invalid cut")
+}
+
+fn millis(time: Duration) -> f64 {
+    time.as_secs_f64() * 1000.0
+}
+
+pub fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+/// `rows` of instance name and [`Summary`] of `quality` as CSV with a header line.
+pub fn quality_csv(rows: &[(String, Summary)]) -> String {
+    let mut csv = quality::HEADER.join(",");
+    csv.push('\n');
+
+    for (instance, summary) in rows {
+        let mut cells = summary.cells(instance);
+        if summary.time_to_target.is_none() {
+            cells[9].clear();
+        }
+
+        let cells: Vec<String> = cells.iter().map(|cell| csv_field(cell)).collect();
+        csv.push_str(&cells.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
@@ -0,0 +1,61 @@
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, available_parallelism};
+
+/// Number of worker threads used by the parallel algorithms.
+pub fn cores() -> usize {
+    available_parallelism()
+        .unwrap_or(NonZeroUsize::new(8).unwrap())
+        .get()
+}
+
+/// Applies `f` to every item on a fixed number of scoped worker threads.
+/// Workers fetch the next unprocessed item themselves, so large and small
+/// items are balanced without any locking.
+/// The results are returned in the order of `items`.
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = cores().min(items.len()).max(1);
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<R>> = items.iter().map(|_| None).collect();
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = vec![];
+
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        if i >= items.len() {
+                            break done;
+                        }
+
+                        done.push((i, f(&items[i])));
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            for (i, result) in handle.join().unwrap() {
+                results[i] = Some(result);
+            }
+        }
+    });
+
+    results.into_iter().map(Option::unwrap).collect()
+}
+
+/// Seed of the `index`-th independent generator derived from `seed`, mixed by
+/// SplitMix64 so that neighboring seeds and indices give unrelated generators.
+pub fn split_seed(seed: u64, index: u64) -> u64 {
+    let mut z = seed.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,497 @@
+//! Exact Max-Cut for planar graphs in polynomial time.
+//!
+//! A set of edges of a plane graph is a cut iff every face has an even number
+//! of them. So the edges left uncut form a set meeting every odd face an odd
+//! number of times, every even face an even number of times: a `T`-join in the
+//! dual graph, where `T` are the odd faces. A minimum `T`-join consists of
+//! shortest dual paths between the pairs of a minimum weight perfect matching
+//! of `T`.
+//!
+//! Every block is embedded on its own with the path addition algorithm of
+//! Demoucron, Malgrange and Pertuiset, the blocks are combined by
+//! [`decompose::solve_blocks`].
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::decompose;
+use crate::graph::{Edge, Graph};
+use crate::matching;
+
+/// Error indicating that a graph passed to [`max_cut_planar`] isn't planar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotPlanarError;
+
+impl std::error::Error for NotPlanarError {}
+
+impl fmt::Display for NotPlanarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Graph is not planar!")
+    }
+}
+
+pub fn is_planar(graph: &Graph) -> bool {
+    decompose::blocks(graph)
+        .iter()
+        .filter(|block| block.len() > 2)
+        .all(|block| embed(&graph.induced_subgraph(block)).is_some())
+}
+
+/// Calculates a maximum cut of the planar `graph`.
+///
+/// # Errors
+///
+/// Returns [`NotPlanarError`] if a block of `graph` isn't planar.
+pub fn max_cut_planar(graph: &Graph) -> Result<Vec<Edge>, NotPlanarError> {
+    decompose::solve_blocks(graph, max_cut_biconnected)
+}
+
+/// Returns the faces of a plane embedding of the biconnected `graph` with at
+/// least three vertices, every face as the cyclic sequence of its vertices.
+/// Returns `None` if `graph` isn't planar.
+pub fn embed(graph: &Graph) -> Option<Vec<Vec<usize>>> {
+    let n = graph.size();
+    let m = graph.edge_size();
+
+    if m > 3 * n - 6 {
+        return None;
+    }
+
+    let mut embedding = Embedding::new(graph, find_cycle(graph));
+
+    while embedding.edges.len() < m {
+        let fragments = embedding.fragments();
+        let mut chosen = None;
+
+        for fragment in &fragments {
+            let faces = embedding.admissible_faces(&fragment.contacts);
+
+            match faces[..] {
+                [] => return None,
+                [face] => {
+                    chosen = Some((fragment, face));
+                    break;
+                }
+                [face, ..] if chosen.is_none() => chosen = Some((fragment, face)),
+                _ => continue,
+            }
+        }
+
+        let (fragment, face) = chosen?;
+        let path = embedding.fragment_path(fragment);
+        embedding.add_path(face, &path);
+    }
+
+    Some(embedding.faces)
+}
+
+/// Maximum cut of a biconnected graph with at least three vertices.
+fn max_cut_biconnected(graph: &Graph) -> Result<Vec<Edge>, NotPlanarError> {
+    let faces = embed(graph).ok_or(NotPlanarError)?;
+    let edges = graph.all_edges();
+
+    let index: HashMap<(usize, usize), usize> = edges
+        .iter()
+        .enumerate()
+        .map(|(i, edge)| ((edge.0, edge.1), i))
+        .collect();
+
+    // Dual graph as (face, edge) pairs per face
+    let mut dual = vec![vec![]; faces.len()];
+    let mut sides_of: Vec<Vec<usize>> = vec![vec![]; edges.len()];
+
+    for (f, face) in faces.iter().enumerate() {
+        for i in 0..face.len() {
+            let (a, b) = (face[i], face[(i + 1) % face.len()]);
+            sides_of[index[&(a.min(b), a.max(b))]].push(f);
+        }
+    }
+
+    for (e, sides) in sides_of.iter().enumerate() {
+        dual[sides[0]].push((sides[1], e));
+        dual[sides[1]].push((sides[0], e));
+    }
+
+    let odd: Vec<usize> = (0..faces.len()).filter(|f| faces[*f].len() % 2 == 1).collect();
+
+    // Minimum weight perfect matching on the odd faces by dual distance
+    let distances: Vec<Vec<usize>> = odd.iter().map(|f| bfs(&dual, *f).0).collect();
+    let max_distance = distances.iter().flatten().copied().max().unwrap_or(0) as i64;
+
+    let mut pairs = vec![];
+    for (i, distance) in distances.iter().enumerate() {
+        for (j, face) in odd.iter().enumerate().skip(i + 1) {
+            pairs.push((i, j, max_distance + 1 - distance[*face] as i64));
+        }
+    }
+
+    let mate = matching::max_weight_matching(odd.len(), &pairs, true);
+
+    let mut uncut = vec![false; edges.len()];
+    for (i, j) in mate.iter().enumerate() {
+        let j = j.expect("odd faces have no perfect matching");
+        if i > j {
+            continue;
+        }
+
+        let (_, parent) = bfs(&dual, odd[i]);
+        let mut face = odd[j];
+
+        while face != odd[i] {
+            let (previous, e) = parent[face];
+            uncut[e] = !uncut[e];
+            face = previous;
+        }
+    }
+
+    Ok(edges
+        .into_iter()
+        .zip(uncut)
+        .filter(|(_, uncut)| !uncut)
+        .map(|(edge, _)| edge)
+        .collect())
+}
+
+/// Breadth first search in the dual graph.
+/// Returns the distances and the (face, edge) every face was reached through.
+fn bfs(dual: &[Vec<(usize, usize)>], start: usize) -> (Vec<usize>, Vec<(usize, usize)>) {
+    let mut distance = vec![usize::MAX; dual.len()];
+    let mut parent = vec![(usize::MAX, usize::MAX); dual.len()];
+    distance[start] = 0;
+    let mut queue = vec![start];
+    let mut i = 0;
+
+    while i < queue.len() {
+        let face = queue[i];
+        i += 1;
+
+        for (next, e) in &dual[face] {
+            if distance[*next] == usize::MAX {
+                distance[*next] = distance[face] + 1;
+                parent[*next] = (face, *e);
+                queue.push(*next);
+            }
+        }
+    }
+
+    (distance, parent)
+}
+
+/// Finds a cycle by depth first search, `graph` has to contain one.
+fn find_cycle(graph: &Graph) -> Vec<usize> {
+    let mut parent = vec![usize::MAX; graph.size()];
+    let mut depth = vec![usize::MAX; graph.size()];
+    depth[0] = 0;
+    let mut stack = vec![(0, 0)];
+
+    while let Some(frame) = stack.last_mut() {
+        let (vertex, next) = *frame;
+
+        let Some(neigh) = graph.neighbors(vertex).get(next).copied() else {
+            stack.pop();
+            continue;
+        };
+        frame.1 += 1;
+
+        if depth[neigh] == usize::MAX {
+            depth[neigh] = depth[vertex] + 1;
+            parent[neigh] = vertex;
+            stack.push((neigh, 0));
+        } else if neigh != parent[vertex] && depth[neigh] < depth[vertex] {
+            let mut cycle = vec![vertex];
+            let mut current = vertex;
+
+            while current != neigh {
+                current = parent[current];
+                cycle.push(current);
+            }
+
+            return cycle;
+        }
+    }
+
+    panic!("graph has no cycle");
+}
+
+/// A fragment of `G` relative to the embedded subgraph `H`: either an edge
+/// between two vertices of `H` or a component of `G - H` with its edges into `H`.
+struct Fragment {
+    /// Vertices of `H` touched by the fragment, sorted
+    contacts: Vec<usize>,
+    /// The vertices of the component, empty for a single edge
+    inner: Vec<usize>,
+}
+
+struct Embedding<'a> {
+    graph: &'a Graph,
+    embedded: Vec<bool>,
+    edges: HashSet<(usize, usize)>,
+    faces: Vec<Vec<usize>>,
+    face_sets: Vec<HashSet<usize>>,
+    /// The faces every vertex lies on
+    faces_of: Vec<Vec<usize>>,
+}
+
+impl<'a> Embedding<'a> {
+    fn new(graph: &'a Graph, cycle: Vec<usize>) -> Embedding<'a> {
+        let mut embedded = vec![false; graph.size()];
+        let mut faces_of = vec![vec![]; graph.size()];
+        let mut edges = HashSet::new();
+
+        for (i, vertex) in cycle.iter().enumerate() {
+            let next = cycle[(i + 1) % cycle.len()];
+            embedded[*vertex] = true;
+            faces_of[*vertex] = vec![0, 1];
+            edges.insert((*vertex.min(&next), *vertex.max(&next)));
+        }
+
+        let set: HashSet<usize> = cycle.iter().copied().collect();
+        let mut reversed = cycle.clone();
+        reversed.reverse();
+
+        Embedding {
+            graph,
+            embedded,
+            edges,
+            faces: vec![cycle, reversed],
+            face_sets: vec![set.clone(), set],
+            faces_of,
+        }
+    }
+
+    fn fragments(&self) -> Vec<Fragment> {
+        let mut fragments = vec![];
+
+        // Edges between embedded vertices
+        for vertex in (0..self.graph.size()).filter(|v| self.embedded[*v]) {
+            for neigh in self.graph.neighbors(vertex) {
+                if *neigh > vertex
+                    && self.embedded[*neigh]
+                    && !self.edges.contains(&(vertex, *neigh))
+                {
+                    fragments.push(Fragment {
+                        contacts: vec![vertex, *neigh],
+                        inner: vec![],
+                    });
+                }
+            }
+        }
+
+        // Components of the vertices not yet embedded
+        let mut visited = self.embedded.clone();
+
+        for start in 0..self.graph.size() {
+            if visited[start] {
+                continue;
+            }
+
+            visited[start] = true;
+            let mut inner = vec![start];
+            let mut contacts = vec![];
+            let mut i = 0;
+
+            while i < inner.len() {
+                let vertex = inner[i];
+                i += 1;
+
+                for neigh in self.graph.neighbors(vertex) {
+                    if self.embedded[*neigh] {
+                        contacts.push(*neigh);
+                    } else if !visited[*neigh] {
+                        visited[*neigh] = true;
+                        inner.push(*neigh);
+                    }
+                }
+            }
+
+            contacts.sort_unstable();
+            contacts.dedup();
+            fragments.push(Fragment { contacts, inner });
+        }
+
+        fragments
+    }
+
+    fn admissible_faces(&self, contacts: &[usize]) -> Vec<usize> {
+        self.faces_of[contacts[0]]
+            .iter()
+            .copied()
+            .filter(|face| contacts.iter().all(|c| self.face_sets[*face].contains(c)))
+            .collect()
+    }
+
+    /// A path through `fragment` between two of its contacts.
+    fn fragment_path(&self, fragment: &Fragment) -> Vec<usize> {
+        if fragment.inner.is_empty() {
+            return fragment.contacts.clone();
+        }
+
+        let first = fragment.contacts[0];
+        let inner: HashSet<usize> = fragment.inner.iter().copied().collect();
+        let start = *self
+            .graph
+            .neighbors(first)
+            .iter()
+            .find(|v| inner.contains(v))
+            .unwrap();
+
+        let mut parent = HashMap::from([(start, usize::MAX)]);
+        let mut queue = vec![start];
+        let mut i = 0;
+
+        while i < queue.len() {
+            let vertex = queue[i];
+            i += 1;
+
+            for neigh in self.graph.neighbors(vertex) {
+                if self.embedded[*neigh] && *neigh != first {
+                    // Found the second contact
+                    let mut path = vec![*neigh];
+                    let mut current = vertex;
+
+                    while current != usize::MAX {
+                        path.push(current);
+                        current = parent[&current];
+                    }
+
+                    path.push(first);
+                    path.reverse();
+                    return path;
+                }
+
+                if inner.contains(neigh) && !parent.contains_key(neigh) {
+                    parent.insert(*neigh, vertex);
+                    queue.push(*neigh);
+                }
+            }
+        }
+
+        panic!("fragment of a biconnected graph with a single contact");
+    }
+
+    /// Embeds `path` between two vertices of `face` into it, splitting it in two.
+    fn add_path(&mut self, face: usize, path: &[usize]) {
+        let (u, v) = (path[0], path[path.len() - 1]);
+        let old = std::mem::take(&mut self.faces[face]);
+        let len = old.len();
+        let i = old.iter().position(|x| *x == u).unwrap();
+        let j = old.iter().position(|x| *x == v).unwrap();
+
+        // u ... v along the face, then back along the path
+        let mut first: Vec<usize> = (0..=(j + len - i) % len).map(|k| old[(i + k) % len]).collect();
+        first.extend(path[1..path.len() - 1].iter().rev());
+
+        // v ... u along the face, then forth along the path
+        let mut second: Vec<usize> = (0..=(i + len - j) % len).map(|k| old[(j + k) % len]).collect();
+        second.extend(&path[1..path.len() - 1]);
+
+        let new = self.faces.len();
+        let old_set = std::mem::take(&mut self.face_sets[face]);
+        let first_set: HashSet<usize> = first.iter().copied().collect();
+        let second_set: HashSet<usize> = second.iter().copied().collect();
+
+        for vertex in &old_set {
+            if !first_set.contains(vertex) {
+                self.faces_of[*vertex].retain(|f| *f != face);
+            }
+            if second_set.contains(vertex) {
+                self.faces_of[*vertex].push(new);
+            }
+        }
+
+        for vertex in &path[1..path.len() - 1] {
+            self.embedded[*vertex] = true;
+            self.faces_of[*vertex] = vec![face, new];
+        }
+
+        for edge in path.windows(2) {
+            self.edges.insert((edge[0].min(edge[1]), edge[0].max(edge[1])));
+        }
+
+        self.faces[face] = first;
+        self.face_sets[face] = first_set;
+        self.faces.push(second);
+        self.face_sets.push(second_set);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brute_force;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    /// Random planar graph: repeatedly insert a vertex into a triangle of a
+    /// triangulation, then remove some of the edges.
+    fn random_planar(rng: &mut SmallRng, n: usize) -> Graph {
+        let mut triangles = vec![(0, 1, 2)];
+        let mut edges = vec![(0, 1), (1, 2), (0, 2)];
+
+        for v in 3..n {
+            let (a, b, c) = triangles.swap_remove(rng.gen_range(0..triangles.len()));
+            triangles.extend([(a, b, v), (b, c, v), (a, c, v)]);
+            edges.extend([(a, v), (b, v), (c, v)]);
+        }
+
+        let mut graph = Graph::new_empty(n);
+        for edge in edges {
+            if rng.gen_bool(0.8) {
+                graph.add_edge(&edge);
+            }
+        }
+
+        graph
+    }
+
+    #[test]
+    fn non_planar() {
+        let mut k5 = Graph::new_empty(5);
+        let mut k33 = Graph::new_empty(6);
+
+        for u in 0..5 {
+            for v in u + 1..5 {
+                k5.add_edge(&(u, v));
+            }
+        }
+
+        for u in 0..3 {
+            for v in 3..6 {
+                k33.add_edge(&(u, v));
+            }
+        }
+
+        assert!(!is_planar(&k5));
+        assert!(!is_planar(&k33));
+        assert_eq!(max_cut_planar(&k33), Err(NotPlanarError));
+    }
+
+    #[test]
+    fn embedding_of_k4() {
+        let mut k4 = Graph::new_empty(4);
+        k4.add_edges(&[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+
+        let faces = embed(&k4).unwrap();
+
+        assert_eq!(faces.len(), 4);
+        assert!(faces.iter().all(|face| face.len() == 3));
+        assert_eq!(max_cut_planar(&k4).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn random_planar_is_exact() {
+        let mut rng = SmallRng::seed_from_u64(30);
+
+        for _ in 0..100 {
+            let n = rng.gen_range(3..13);
+            let graph = random_planar(&mut rng, n);
+
+            assert!(is_planar(&graph));
+
+            let cut = max_cut_planar(&graph).unwrap();
+
+            assert!(graph.partition(&cut).is_some());
+            let best = brute_force::max_cut_brute_force(&graph, 20).unwrap();
+            assert_eq!(cut.len(), best.len());
+        }
+    }
+}
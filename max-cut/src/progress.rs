@@ -0,0 +1,119 @@
+//! Progress events of the solvers as JSON lines, to plot the convergence of a run.
+//!
+//! Every line is one event with the instance, the algorithm and the time since
+//! the start of the run:
+//!
+//! ```text
+//! {"event":"start","file":"g.gr","algorithm":"ilp","elapsed_ms":0.000,"vertices":60,"edges":885}
+//! {"event":"bound","file":"g.gr","algorithm":"ilp","elapsed_ms":0.015,"value":885}
+//! {"event":"incumbent","file":"g.gr","algorithm":"ilp","elapsed_ms":812.544,"value":535,"iteration":1}
+//! {"event":"finish","file":"g.gr","algorithm":"ilp","elapsed_ms":812.601,"value":535,"exact":true,"interrupted":false}
+//! ```
+//!
+//! `restart` events carry the iteration like `incumbent`. All values refer to
+//! the whole graph, with preprocessing only the cut of all blocks or kernels
+//! together is reported once they are solved.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use clap::ValueEnum;
+use max_cut::control::{Event, RunControl};
+
+use crate::output::{json_string, Instance, Outcome};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressFormat {
+    /// One JSON object per line and event
+    Jsonl,
+}
+
+/// Writes progress events to stderr or a file.
+#[derive(Clone)]
+pub struct Progress {
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+}
+
+impl Progress {
+    /// Writes to `path` or to stderr if it isn't given.
+    pub fn new(path: Option<&Path>) -> io::Result<Progress> {
+        let writer: Box<dyn Write + Send> = match path {
+            Some(path) => Box::new(io::LineWriter::new(fs::File::create(path)?)),
+            None => Box::new(io::stderr()),
+        };
+
+        Ok(Progress {
+            writer: Arc::new(Mutex::new(writer)),
+        })
+    }
+
+    /// Writes the start event of `algorithm` on `instance` and returns `control`
+    /// writing the events of the run.
+    pub fn observe(&self, instance: &Instance, algorithm: &str, control: RunControl) -> RunControl {
+        let prefix = prefix(instance, algorithm);
+        let graph = &instance.graph;
+
+        self.write(
+            &prefix,
+            "start",
+            Duration::ZERO,
+            &format!(",\"vertices\":{},\"edges\":{}", graph.size(), graph.edge_size()),
+        );
+
+        let progress = self.clone();
+
+        control.on_event(move |event| match event {
+            Event::Incumbent(incumbent) => progress.write(
+                &prefix,
+                "incumbent",
+                incumbent.elapsed,
+                &format!(",\"value\":{},\"iteration\":{}", incumbent.value, incumbent.iteration),
+            ),
+            Event::Bound { value, elapsed } => {
+                progress.write(&prefix, "bound", *elapsed, &format!(",\"value\":{value}"))
+            }
+            Event::Restart { iteration, elapsed } => {
+                progress.write(&prefix, "restart", *elapsed, &format!(",\"iteration\":{iteration}"))
+            }
+        })
+    }
+
+    /// Writes the finish event of `outcome` on `instance`.
+    pub fn finish(&self, instance: &Instance, outcome: &Outcome, elapsed: Duration) {
+        self.write(
+            &prefix(instance, &outcome.algorithm.to_string()),
+            "finish",
+            elapsed,
+            &format!(
+                ",\"value\":{},\"exact\":{},\"interrupted\":{}",
+                outcome.cut.len(),
+                outcome.exact,
+                outcome.interrupted
+            ),
+        );
+    }
+
+    fn write(&self, prefix: &str, event: &str, elapsed: Duration, fields: &str) {
+        let mut writer = self.writer.lock().unwrap();
+
+        // Progress is informational, a failing writer must not stop the run
+        let _ = writeln!(
+            writer,
+            "{{\"event\":\"{event}\",{prefix},\"elapsed_ms\":{:.3}{fields}}}",
+            elapsed.as_secs_f64() * 1000.0
+        );
+    }
+}
+
+fn prefix(instance: &Instance, algorithm: &str) -> String {
+    format!(
+        "\"file\":{},\"algorithm\":{}",
+        json_string(instance.file.to_str().unwrap()),
+        json_string(algorithm)
+    )
+}
@@ -0,0 +1,277 @@
+//! Solution quality of the algorithms over many seeds.
+//!
+//! The criterion benchmarks only measure the running time. [`sample`] runs an
+//! [`Algorithm`] once per seed and [`Summary`] condenses the cut values and
+//! running times, measured against a target value like the best known cut.
+
+use std::fmt::Write;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use crate::graph::Graph;
+use crate::solver::{Algorithm, SolverError};
+
+/// Names of the columns of [`Summary::cells`].
+pub const HEADER: [&str; 10] = [
+    "instance",
+    "algorithm",
+    "runs",
+    "best",
+    "mean",
+    "std",
+    "gap_percent",
+    "success_percent",
+    "time_ms",
+    "time_to_target_ms",
+];
+
+/// One run of an algorithm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub seed: u64,
+    /// Size of the cut found
+    pub value: usize,
+    pub time: Duration,
+}
+
+/// Statistics over the [`Sample`]s of one algorithm on one graph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub algorithm: Algorithm,
+    pub runs: usize,
+    pub best: usize,
+    pub mean: f64,
+    /// Sample standard deviation of the cut values
+    pub std: f64,
+    pub mean_time: Duration,
+    /// Value a run has to reach to count as a success
+    pub target: usize,
+    /// Fraction of the runs reaching `target`
+    pub success_rate: f64,
+    /// Expected time until `target` is reached when restarting the algorithm
+    /// with new seeds: the total time divided by the number of successes.
+    /// `None` if no run reached `target`.
+    pub time_to_target: Option<Duration>,
+}
+
+impl Summary {
+    /// Summarizes `samples` of `algorithm`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` is empty.
+    pub fn new(algorithm: Algorithm, samples: &[Sample], target: usize) -> Summary {
+        assert!(!samples.is_empty(), "no samples to summarize");
+
+        let runs = samples.len();
+        let mean = samples.iter().map(|sample| sample.value as f64).sum::<f64>() / runs as f64;
+        let variance = if runs > 1 {
+            samples
+                .iter()
+                .map(|sample| (sample.value as f64 - mean).powi(2))
+                .sum::<f64>()
+                / (runs - 1) as f64
+        } else {
+            0.0
+        };
+
+        let total_time: Duration = samples.iter().map(|sample| sample.time).sum();
+        let successes = samples.iter().filter(|sample| sample.value >= target).count();
+
+        Summary {
+            algorithm,
+            runs,
+            best: samples.iter().map(|sample| sample.value).max().unwrap(),
+            mean,
+            std: variance.sqrt(),
+            mean_time: total_time / runs as u32,
+            target,
+            success_rate: successes as f64 / runs as f64,
+            time_to_target: (successes > 0).then(|| total_time / successes as u32),
+        }
+    }
+
+    /// Relative distance of the mean cut value below `target`.
+    pub fn gap(&self) -> f64 {
+        if self.target == 0 {
+            0.0
+        } else {
+            (self.target as f64 - self.mean) / self.target as f64
+        }
+    }
+
+    /// The columns of [`HEADER`] for the row of `instance`.
+    pub fn cells(&self, instance: &str) -> Vec<String> {
+        vec![
+            instance.to_owned(),
+            self.algorithm.to_string(),
+            self.runs.to_string(),
+            self.best.to_string(),
+            format!("{:.2}", self.mean),
+            format!("{:.2}", self.std),
+            format!("{:.3}", self.gap() * 100.0),
+            format!("{:.1}", self.success_rate * 100.0),
+            format!("{:.3}", self.mean_time.as_secs_f64() * 1000.0),
+            self.time_to_target
+                .map_or(String::from("-"), |time| format!("{:.3}", time.as_secs_f64() * 1000.0)),
+        ]
+    }
+}
+
+/// Runs `algorithm` on `graph` once for every seed of `seeds`, or only with
+/// the first seed if the algorithm [`Algorithm::is_randomized`] isn't.
+///
+/// # Errors
+///
+/// Returns the [`SolverError`] of the first failing run.
+pub fn sample(graph: &Graph, algorithm: Algorithm, seeds: Range<u64>) -> Result<Vec<Sample>, SolverError> {
+    let seeds = match algorithm.is_randomized() {
+        true => seeds,
+        false => seeds.start..seeds.end.min(seeds.start + 1),
+    };
+
+    seeds
+        .map(|seed| {
+            let start = Instant::now();
+            let solution = algorithm.solve_seeded(graph, seed)?;
+
+            Ok(Sample {
+                seed,
+                value: solution.cut.len(),
+                time: start.elapsed(),
+            })
+        })
+        .collect()
+}
+
+/// [`Summary`] of every algorithm of `algorithms` on `graph` over `seeds`.
+/// The target is `best_known` if given, else the best cut found by any run.
+/// Algorithms that don't apply to `graph` are left out.
+///
+/// # Errors
+///
+/// Returns the [`SolverError`] of the first failing run.
+pub fn compare(
+    graph: &Graph,
+    algorithms: &[Algorithm],
+    seeds: Range<u64>,
+    best_known: Option<usize>,
+) -> Result<Vec<Summary>, SolverError> {
+    let mut samples = vec![];
+
+    for algorithm in algorithms {
+        match sample(graph, *algorithm, seeds.clone()) {
+            Ok(runs) if !runs.is_empty() => samples.push((*algorithm, runs)),
+            Ok(_) => {}
+            Err(err) if err.is_inapplicable() => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    let target = best_known.unwrap_or_else(|| {
+        samples
+            .iter()
+            .flat_map(|(_, runs)| runs.iter().map(|run| run.value))
+            .max()
+            .unwrap_or(0)
+    });
+
+    Ok(samples
+        .iter()
+        .map(|(algorithm, runs)| Summary::new(*algorithm, runs, target))
+        .collect())
+}
+
+/// Table of `rows` of instance name and [`Summary`] with aligned columns.
+pub fn table(rows: &[(String, Summary)]) -> String {
+    let mut lines: Vec<Vec<String>> = vec![HEADER.iter().map(|cell| cell.to_string()).collect()];
+    lines.extend(rows.iter().map(|(instance, summary)| summary.cells(instance)));
+
+    let widths: Vec<usize> = (0..HEADER.len())
+        .map(|column| lines.iter().map(|line| line[column].len()).max().unwrap())
+        .collect();
+
+    let mut table = String::new();
+
+    for line in lines {
+        let cells: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                // Names to the left, numbers to the right
+                if column < 2 {
+                    format!("{cell:<width$}")
+                } else {
+                    format!("{cell:>width$}")
+                }
+            })
+            .collect();
+
+        writeln!(table, "{}", cells.join("  ").trim_end()).unwrap();
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+
+    fn samples(values: &[usize]) -> Vec<Sample> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(seed, value)| Sample {
+                seed: seed as u64,
+                value: *value,
+                time: Duration::from_millis(10),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn statistics() {
+        let summary = Summary::new(Algorithm::Random, &samples(&[8, 10, 12, 10]), 12);
+
+        assert_eq!(summary.best, 12);
+        assert_eq!(summary.mean, 10.0);
+        assert!((summary.std - (8.0f64 / 3.0).sqrt()).abs() < 1e-9);
+        assert_eq!(summary.success_rate, 0.25);
+        assert_eq!(summary.time_to_target, Some(Duration::from_millis(40)));
+        assert!((summary.gap() - 1.0 / 6.0).abs() < 1e-9);
+
+        let failed = Summary::new(Algorithm::Random, &samples(&[8]), 12);
+        assert_eq!(failed.std, 0.0);
+        assert_eq!(failed.time_to_target, None);
+    }
+
+    #[test]
+    fn seeds_are_reproducible() -> Result<(), SolverError> {
+        let graph = generate::gnp(40, 0.3, 39);
+        let values = |runs: Vec<Sample>| runs.into_iter().map(|run| run.value).collect::<Vec<_>>();
+
+        for algorithm in [Algorithm::Random, Algorithm::RandomParallel] {
+            assert_eq!(
+                values(sample(&graph, algorithm, 0..5)?),
+                values(sample(&graph, algorithm, 0..5)?)
+            );
+        }
+
+        let summaries = compare(
+            &graph,
+            &[Algorithm::Random, Algorithm::GreedyImproved, Algorithm::Planar],
+            0..5,
+            None,
+        )?;
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].runs, 5);
+        assert_eq!(summaries[1].runs, 1);
+        assert!(summaries.iter().any(|summary| summary.success_rate > 0.0));
+        assert_eq!(table(&[(String::from("g"), summaries[0].clone())]).lines().count(), 2);
+
+        Ok(())
+    }
+}
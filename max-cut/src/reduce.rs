@@ -0,0 +1,456 @@
+//! Data reduction for Max-Cut.
+//!
+//! [`Kernel::new`] repeatedly applies the following rules, all of which keep
+//! at least one maximum cut intact:
+//!
+//! * An isolated vertex is removed.
+//! * A vertex `v` of degree one is removed. Its edge is always cut.
+//! * A vertex `v` of degree two whose neighbours `a` and `b` are adjacent is
+//!   removed together with the edge `(a, b)`. Putting `v` opposite of `a` cuts
+//!   exactly two of the three edges, whatever the sides of `a` and `b`.
+//! * A path `a - v - u - b` through two vertices of degree two is replaced by
+//!   the edge `(a, b)` if `a` and `b` aren't adjacent. The path cuts
+//!   two edges more than the new edge.
+//! * Every cut vertex is split into one copy per block, making the blocks
+//!   separate components.
+//!
+//! Merging twin vertices would need edge weights, which [`Graph`] doesn't support.
+//! The removed vertices are recorded on a stack that [`Kernel::lift`] replays
+//! backwards, to turn a cut of the kernel into a cut of the original graph.
+
+use std::collections::BTreeSet;
+
+use crate::decompose;
+use crate::graph::{Edge, Graph};
+
+/// How often each rule of [`Kernel::new`] was applied.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RuleCounts {
+    pub isolated: usize,
+    pub degree_one: usize,
+    pub triangle: usize,
+    pub path: usize,
+    pub split: usize,
+}
+
+#[derive(Debug)]
+enum Reduction {
+    /// `vertex` was removed without any edges, so any side will do.
+    Free(usize),
+    /// `vertex` has to be put on the opposite side of `of`.
+    Opposite { vertex: usize, of: usize },
+    /// The `block` containing `copy` was split off at `vertex`.
+    Split {
+        vertex: usize,
+        copy: usize,
+        block: Vec<usize>,
+    },
+}
+
+/// The reduced instance of a [`Graph`].
+pub struct Kernel<'a> {
+    original: &'a Graph,
+    graph: Graph,
+    /// `vertices[i]` is the working vertex behind vertex `i` of the kernel
+    vertices: Vec<usize>,
+    /// Size of the working vertex set, including the copies of cut vertices
+    working_size: usize,
+    offset: usize,
+    reductions: Vec<Reduction>,
+    counts: RuleCounts,
+}
+
+impl<'a> Kernel<'a> {
+    /// Reduces `graph` until no rule applies any more.
+    pub fn new(graph: &'a Graph) -> Kernel<'a> {
+        let mut reducer = Reducer {
+            // Self-loops are never cut, so the rules ignore them
+            adjacency: (0..graph.size())
+                .map(|vertex| {
+                    graph
+                        .neighbors(vertex)
+                        .iter()
+                        .copied()
+                        .filter(|neigh| *neigh != vertex)
+                        .collect()
+                })
+                .collect(),
+            alive: vec![true; graph.size()],
+            offset: 0,
+            reductions: vec![],
+            counts: RuleCounts::default(),
+        };
+
+        let mut queue: Vec<usize> = (0..graph.size()).rev().collect();
+
+        loop {
+            while let Some(vertex) = queue.pop() {
+                reducer.reduce(vertex, &mut queue);
+            }
+
+            queue = reducer.split();
+
+            if queue.is_empty() {
+                break;
+            }
+        }
+
+        let (kernel, vertices) = reducer.compact();
+
+        Kernel {
+            original: graph,
+            graph: kernel,
+            vertices,
+            working_size: reducer.alive.len(),
+            offset: reducer.offset,
+            reductions: reducer.reductions,
+            counts: reducer.counts,
+        }
+    }
+
+    /// The reduced graph.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Number of edges the reductions cut in addition to a cut of the kernel.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn counts(&self) -> RuleCounts {
+        self.counts
+    }
+
+    /// Turns a cut of [`Kernel::graph`] into a cut of the original graph
+    /// with [`Kernel::offset`] more edges. A maximum cut of the kernel is
+    /// lifted to a maximum cut of the original graph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cut` isn't a cut of the kernel.
+    pub fn lift(&self, cut: &[Edge]) -> Vec<Edge> {
+        let kernel_sides = self
+            .graph
+            .partition(cut)
+            .expect("lifted edges are no cut of the kernel");
+
+        let mut sides = vec![false; self.working_size];
+        for (vertex, side) in self.vertices.iter().zip(kernel_sides) {
+            sides[*vertex] = side;
+        }
+
+        for reduction in self.reductions.iter().rev() {
+            match reduction {
+                Reduction::Free(vertex) => sides[*vertex] = false,
+                Reduction::Opposite { vertex, of } => sides[*vertex] = !sides[*of],
+                Reduction::Split {
+                    vertex,
+                    copy,
+                    block,
+                } => {
+                    if sides[*vertex] != sides[*copy] {
+                        block.iter().for_each(|v| sides[*v] = !sides[*v]);
+                    }
+                }
+            }
+        }
+
+        let lifted = self.original.cut(&sides[..self.original.size()]);
+        debug_assert_eq!(lifted.len(), cut.len() + self.offset);
+
+        lifted
+    }
+}
+
+/// Solves the kernel of `graph` with `solver` and lifts the result.
+///
+/// # Errors
+///
+/// Returns the error of `solver`.
+pub fn solve_reduced<F, E>(graph: &Graph, solver: F) -> Result<Vec<Edge>, E>
+where
+    F: Fn(&Graph) -> Result<Vec<Edge>, E>,
+{
+    let kernel = Kernel::new(graph);
+
+    let cut = if kernel.graph().edge_size() == 0 {
+        vec![]
+    } else {
+        solver(kernel.graph())?
+    };
+
+    Ok(kernel.lift(&cut))
+}
+
+struct Reducer {
+    adjacency: Vec<BTreeSet<usize>>,
+    alive: Vec<bool>,
+    offset: usize,
+    reductions: Vec<Reduction>,
+    counts: RuleCounts,
+}
+
+impl Reducer {
+    /// Applies the first matching rule to `vertex` and queues the vertices
+    /// whose degree changed.
+    fn reduce(&mut self, vertex: usize, queue: &mut Vec<usize>) {
+        if !self.alive[vertex] || self.adjacency[vertex].len() > 2 {
+            return;
+        }
+
+        let neighbors: Vec<usize> = self.adjacency[vertex].iter().copied().collect();
+
+        match neighbors[..] {
+            [] => {
+                self.remove(vertex);
+                self.reductions.push(Reduction::Free(vertex));
+                self.counts.isolated += 1;
+            }
+            [neigh] => {
+                self.remove(vertex);
+                self.reductions.push(Reduction::Opposite { vertex, of: neigh });
+                self.offset += 1;
+                self.counts.degree_one += 1;
+                queue.push(neigh);
+            }
+            [a, b] if self.adjacency[a].contains(&b) => {
+                self.remove(vertex);
+                self.adjacency[a].remove(&b);
+                self.adjacency[b].remove(&a);
+                self.reductions.push(Reduction::Opposite { vertex, of: a });
+                self.offset += 2;
+                self.counts.triangle += 1;
+                queue.extend([a, b]);
+            }
+            [a, b] => {
+                // Look for a path end - vertex - inner - other_end
+                let Some((inner, end)) = [(a, b), (b, a)]
+                    .into_iter()
+                    .find(|(inner, _)| self.adjacency[*inner].len() == 2)
+                else {
+                    return;
+                };
+
+                let other_end = *self.adjacency[inner]
+                    .iter()
+                    .find(|v| **v != vertex)
+                    .unwrap();
+
+                if self.adjacency[end].contains(&other_end) {
+                    return;
+                }
+
+                self.remove(vertex);
+                self.remove(inner);
+                self.adjacency[end].insert(other_end);
+                self.adjacency[other_end].insert(end);
+                // Lifted in reverse, `vertex` has to be placed first
+                self.reductions.push(Reduction::Opposite {
+                    vertex: inner,
+                    of: vertex,
+                });
+                self.reductions.push(Reduction::Opposite { vertex, of: end });
+                self.offset += 2;
+                self.counts.path += 1;
+                queue.extend([end, other_end]);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn remove(&mut self, vertex: usize) {
+        for neigh in std::mem::take(&mut self.adjacency[vertex]) {
+            self.adjacency[neigh].remove(&vertex);
+        }
+
+        self.alive[vertex] = false;
+    }
+
+    /// Splits all cut vertices and returns the vertices whose degree changed.
+    fn split(&mut self) -> Vec<usize> {
+        let (graph, vertices) = self.compact();
+        let blocks: Vec<Vec<usize>> = decompose::blocks(&graph)
+            .into_iter()
+            .map(|block| block.into_iter().map(|v| vertices[v]).collect())
+            .collect();
+
+        let mut blocks_of = vec![vec![]; self.alive.len()];
+        for (i, block) in blocks.iter().enumerate() {
+            block.iter().for_each(|vertex| blocks_of[*vertex].push(i));
+        }
+
+        // Walk the block-cut tree and remember through which cut vertex
+        // every block was reached
+        let mut reached: Vec<Option<usize>> = vec![None; blocks.len()];
+        let mut order = vec![];
+
+        for root in 0..blocks.len() {
+            if reached[root].is_some() {
+                continue;
+            }
+
+            reached[root] = Some(usize::MAX);
+            let mut queue = vec![root];
+
+            while let Some(i) = queue.pop() {
+                order.push(i);
+
+                for vertex in &blocks[i] {
+                    for next in &blocks_of[*vertex] {
+                        if reached[*next].is_none() {
+                            reached[*next] = Some(*vertex);
+                            queue.push(*next);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Parents have to be lifted before their children,
+        // so the children are pushed first
+        let mut changed = vec![];
+
+        for i in order.into_iter().rev() {
+            let vertex = match reached[i] {
+                Some(vertex) if vertex != usize::MAX => vertex,
+                _ => continue,
+            };
+
+            let copy = self.alive.len();
+            self.alive.push(true);
+            self.adjacency.push(BTreeSet::new());
+
+            let mut block = blocks[i].clone();
+
+            for other in block.iter_mut() {
+                if *other == vertex {
+                    *other = copy;
+                } else if self.adjacency[vertex].remove(other) {
+                    self.adjacency[*other].remove(&vertex);
+                    self.adjacency[*other].insert(copy);
+                    self.adjacency[copy].insert(*other);
+                }
+            }
+
+            self.reductions.push(Reduction::Split {
+                vertex,
+                copy,
+                block,
+            });
+            self.counts.split += 1;
+            changed.extend([vertex, copy]);
+        }
+
+        changed
+    }
+
+    /// Returns the graph of the alive vertices and their working ids.
+    fn compact(&self) -> (Graph, Vec<usize>) {
+        let vertices: Vec<usize> = (0..self.alive.len()).filter(|v| self.alive[*v]).collect();
+
+        let mut index = vec![usize::MAX; self.alive.len()];
+        for (i, vertex) in vertices.iter().enumerate() {
+            index[*vertex] = i;
+        }
+
+        let mut graph = Graph::new_empty(vertices.len());
+        for vertex in &vertices {
+            for neigh in self.adjacency[*vertex].range(vertex + 1..) {
+                graph.add_edge(&(index[*vertex], index[*neigh]));
+            }
+        }
+
+        (graph, vertices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brute_force::{self, LIMIT};
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    #[test]
+    fn tree_vanishes() {
+        let mut graph = Graph::new_empty(7);
+        graph.add_edges(&[(0, 1), (0, 2), (1, 3), (1, 4), (2, 5), (2, 6)]);
+
+        let kernel = Kernel::new(&graph);
+
+        assert_eq!(kernel.graph().size(), 0);
+        assert_eq!(kernel.offset(), 6);
+        assert_eq!(kernel.lift(&[]), graph.all_edges());
+    }
+
+    #[test]
+    fn odd_cycle_vanishes() {
+        let mut graph = Graph::new_empty(5);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+
+        let kernel = Kernel::new(&graph);
+
+        assert_eq!(kernel.graph().size(), 0);
+        assert_eq!(kernel.lift(&[]).len(), 4);
+    }
+
+    #[test]
+    fn blocks_are_split() {
+        // Two K4 sharing vertex 3
+        let mut graph = Graph::new_empty(7);
+        graph.add_edges(&[
+            (0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3),
+            (3, 4), (3, 5), (3, 6), (4, 5), (4, 6), (5, 6),
+        ]);
+
+        let kernel = Kernel::new(&graph);
+
+        assert_eq!(kernel.counts().split, 1);
+        assert_eq!(kernel.graph().size(), 8);
+        let cut = brute_force::max_cut_brute_force(kernel.graph(), LIMIT).unwrap();
+        assert_eq!(kernel.lift(&cut).len(), 8);
+    }
+
+    #[test]
+    fn lifting_keeps_optimality() {
+        let mut rng = SmallRng::seed_from_u64(27);
+
+        for _ in 0..200 {
+            let n = rng.gen_range(1..12);
+            let p = rng.gen_range(0.1..0.6);
+            let mut graph = Graph::new_empty(n);
+
+            for u in 0..n {
+                for v in u + 1..n {
+                    if rng.gen_bool(p) {
+                        graph.add_edge(&(u, v));
+                    }
+                }
+            }
+
+            let kernel = Kernel::new(&graph);
+            let cut = brute_force::max_cut_brute_force(kernel.graph(), LIMIT).unwrap();
+            let lifted = kernel.lift(&cut);
+
+            assert_eq!(lifted.len(), brute_force::max_cut_brute_force(&graph, LIMIT).unwrap().len());
+            assert!(graph.partition(&lifted).is_some());
+        }
+    }
+
+    #[test]
+    fn self_loops_are_ignored() {
+        // A triangle 1 - 2 - 3 with the pendant vertex 0, loops at 0, 1 and the isolated 4
+        let mut graph = Graph::new_empty(5);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 3), (3, 1)]);
+        let maximum = brute_force::max_cut_brute_force(&graph, LIMIT).unwrap().len();
+        graph.add_edges(&[(0, 0), (1, 1), (4, 4)]);
+
+        let kernel = Kernel::new(&graph);
+        let cut = brute_force::max_cut_brute_force(kernel.graph(), LIMIT).unwrap();
+        let lifted = kernel.lift(&cut);
+
+        assert_eq!(lifted.len(), maximum);
+        assert_eq!(lifted.len(), cut.len() + kernel.offset());
+        assert!(lifted.iter().all(|edge| edge.0 != edge.1));
+    }
+}
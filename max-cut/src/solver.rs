@@ -0,0 +1,283 @@
+//! Common interface to all Max-Cut algorithms of the crate.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::sync::Arc;
+
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::approx::{self, VertexOrder};
+use crate::bipartite;
+use crate::brute_force::{self, TooLargeError};
+use crate::control::RunControl;
+use crate::graph::{Edge, Graph};
+use crate::heuristic;
+use crate::ilp;
+use crate::planar::{self, NotPlanarError};
+use crate::spectral;
+use crate::treewidth;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Ilp,
+    Greedy,
+    GreedyImproved,
+    /// Greedy placing the vertices in `order`, followed by up to `passes`
+    /// passes moving single vertices
+    GreedyOrdered { order: VertexOrder, passes: usize },
+    Random,
+    RandomImproved,
+    RandomParallel,
+    /// Best of a pairwise independent family of bipartitions, deterministic
+    Derandomized,
+    /// Sweep along the smallest eigenvector of the adjacency matrix
+    Spectral,
+    /// Trevisan's recursive spectral algorithm
+    Trevisan,
+    /// Odd cycle transversal, exact up to `max_exact` transversal vertices
+    Oct { max_exact: usize },
+    Planar,
+    /// Tree decomposition, falls back to [`Algorithm::GreedyImproved`]
+    /// above `max_width`
+    Treewidth { max_width: usize },
+    BruteForce { max_size: usize },
+}
+
+/// Cut found by an [`Algorithm`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub cut: Vec<Edge>,
+    /// Whether `cut` is guaranteed to be maximum
+    pub exact: bool,
+    /// Width of the tree decomposition used by [`Algorithm::Treewidth`], or
+    /// the width reached before it fell back, `None` for the other algorithms
+    pub width: Option<usize>,
+}
+
+#[derive(Debug)]
+pub enum SolverError {
+    Ilp(good_lp::ResolutionError),
+    NotPlanar(NotPlanarError),
+    TooLarge(TooLargeError),
+    /// A worker thread of a parallel heuristic panicked
+    Panicked,
+}
+
+impl SolverError {
+    /// Whether the algorithm just doesn't apply to the graph,
+    /// as opposed to failing on it.
+    pub fn is_inapplicable(&self) -> bool {
+        matches!(self, SolverError::NotPlanar(_) | SolverError::TooLarge(_))
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::Ilp(err) => write!(f, "ilp error: {err}"),
+            SolverError::NotPlanar(err) => write!(f, "{err}"),
+            SolverError::TooLarge(err) => write!(f, "{err}"),
+            SolverError::Panicked => write!(f, "Worker thread panicked!"),
+        }
+    }
+}
+
+impl From<good_lp::ResolutionError> for SolverError {
+    fn from(err: good_lp::ResolutionError) -> Self {
+        SolverError::Ilp(err)
+    }
+}
+
+impl From<NotPlanarError> for SolverError {
+    fn from(err: NotPlanarError) -> Self {
+        SolverError::NotPlanar(err)
+    }
+}
+
+impl From<TooLargeError> for SolverError {
+    fn from(err: TooLargeError) -> Self {
+        SolverError::TooLarge(err)
+    }
+}
+
+impl Algorithm {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Ilp => "ilp",
+            Algorithm::Greedy => "greedy",
+            Algorithm::GreedyImproved => "greedy-improved",
+            Algorithm::GreedyOrdered { .. } => "greedy-ordered",
+            Algorithm::Random => "random",
+            Algorithm::RandomImproved => "random-improved",
+            Algorithm::RandomParallel => "random-parallel",
+            Algorithm::Derandomized => "derandomized",
+            Algorithm::Spectral => "spectral",
+            Algorithm::Trevisan => "trevisan",
+            Algorithm::Oct { .. } => "oct",
+            Algorithm::Planar => "planar",
+            Algorithm::Treewidth { .. } => "treewidth",
+            Algorithm::BruteForce { .. } => "brute-force",
+        }
+    }
+
+    /// Whether the cut found is at least half of the edges.
+    pub fn is_half_approximation(&self) -> bool {
+        !matches!(
+            self,
+            Algorithm::Greedy | Algorithm::Random | Algorithm::RandomParallel | Algorithm::Spectral
+        )
+    }
+
+    /// Runs the algorithm on `graph`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolverError`] if the algorithm fails or doesn't apply to `graph`.
+    pub fn solve(&self, graph: &Graph) -> Result<Solution, SolverError> {
+        self.solve_controlled(graph, None, &RunControl::new())
+    }
+
+    /// Runs the algorithm on `graph` with its random choices given by `seed`.
+    /// Deterministic algorithms ignore `seed`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolverError`] if the algorithm fails or doesn't apply to `graph`.
+    pub fn solve_seeded(&self, graph: &Graph, seed: u64) -> Result<Solution, SolverError> {
+        self.solve_controlled(graph, Some(seed), &RunControl::new())
+    }
+
+    /// Runs the algorithm on `graph` until it is done or `control` asks it to
+    /// stop, and reports improved cuts and bounds to `control`. An early stop
+    /// returns the best cut found so far, which is then not exact. The
+    /// iterations are
+    ///
+    /// - [`Algorithm::RandomImproved`]: random bipartitions,
+    /// - [`Algorithm::Oct`]: evaluated placements of the transversal,
+    /// - [`Algorithm::BruteForce`]: enumerated prefixes of 8 vertices,
+    /// - [`Algorithm::Ilp`]: solved components, the solver gets the time left.
+    ///
+    /// The other algorithms run in one go and only report their cut.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolverError`] if the algorithm fails or doesn't apply to `graph`.
+    pub fn solve_controlled(
+        &self,
+        graph: &Graph,
+        seed: Option<u64>,
+        control: &RunControl,
+    ) -> Result<Solution, SolverError> {
+        let solution = self.run(graph, seed, control)?;
+        control.improve(&solution.cut);
+
+        if solution.exact {
+            control.bound(solution.cut.len());
+        }
+
+        Ok(solution)
+    }
+
+    /// Whether the cut found depends on random choices.
+    pub fn is_randomized(&self) -> bool {
+        matches!(
+            self,
+            Algorithm::Random
+                | Algorithm::RandomImproved
+                | Algorithm::RandomParallel
+                | Algorithm::GreedyOrdered {
+                    order: VertexOrder::Random,
+                    ..
+                }
+        )
+    }
+
+    fn run(&self, graph: &Graph, seed: Option<u64>, control: &RunControl) -> Result<Solution, SolverError> {
+        let inexact = |cut| {
+            Ok(Solution {
+                cut,
+                exact: false,
+                width: None,
+            })
+        };
+        // Stopping is final, so no stop afterwards means the run was complete
+        let complete = |cut| {
+            Ok(Solution {
+                cut,
+                exact: control.stop().is_none(),
+                width: None,
+            })
+        };
+
+        match *self {
+            Algorithm::Ilp => complete(ilp::MaxCutIlp::new(graph).solve_controlled(control)?),
+            Algorithm::Greedy => inexact(approx::max_cut_greedy(graph)),
+            Algorithm::GreedyImproved => inexact(approx::max_cut_greedy_impr(graph)),
+            Algorithm::GreedyOrdered { order, passes } => {
+                let seed = seed.unwrap_or_else(|| SmallRng::from_entropy().gen());
+                inexact(approx::max_cut_greedy_ordered(graph, &order.order(graph, seed), passes))
+            }
+            Algorithm::Random => inexact(match seed {
+                Some(seed) => heuristic::rand_aprox_seeded(graph, seed),
+                None => heuristic::rand_aprox(graph),
+            }),
+            Algorithm::RandomImproved => {
+                let graph = Arc::new(graph.clone());
+                let cut = heuristic::rand_approx_impr_controlled(graph, seed, control);
+                inexact(cut.map_err(|_| SolverError::Panicked)?)
+            }
+            Algorithm::RandomParallel => {
+                let cut = match seed {
+                    Some(seed) => heuristic::rand_aprox_parallel_seeded(graph, seed),
+                    None => heuristic::rand_aprox_parallel(graph),
+                };
+                inexact(cut.map_err(|_| SolverError::Panicked)?)
+            }
+            Algorithm::Derandomized => inexact(heuristic::rand_aprox_derandomized(graph)),
+            Algorithm::Spectral => inexact(spectral::max_cut_spectral(graph)),
+            Algorithm::Trevisan => inexact(spectral::max_cut_trevisan(graph)),
+            Algorithm::Oct { max_exact } => {
+                let result = bipartite::near_bipartite_controlled(graph, max_exact, control);
+                Ok(Solution {
+                    cut: result.cut,
+                    exact: result.exact,
+                    width: None,
+                })
+            }
+            Algorithm::Planar => Ok(Solution {
+                cut: planar::max_cut_planar(graph)?,
+                exact: true,
+                width: None,
+            }),
+            Algorithm::Treewidth { max_width } => {
+                let result = treewidth::max_cut_treewidth(graph, max_width, |g| {
+                    Ok::<_, Infallible>(approx::max_cut_greedy_impr(g))
+                })
+                .unwrap();
+                Ok(Solution {
+                    cut: result.cut,
+                    exact: result.exact,
+                    width: Some(result.width),
+                })
+            }
+            Algorithm::BruteForce { max_size } => {
+                complete(brute_force::max_cut_brute_force_controlled(graph, max_size, control)?)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Algorithm::GreedyOrdered { order, passes } => write!(f, "greedy-ordered({order} {passes})"),
+            Algorithm::Oct { max_exact } => write!(f, "oct({max_exact})"),
+            Algorithm::Treewidth { max_width } => write!(f, "treewidth({max_width})"),
+            Algorithm::BruteForce { max_size } => write!(f, "brute-force({max_size})"),
+            _ => write!(f, "{}", self.name()),
+        }
+    }
+}
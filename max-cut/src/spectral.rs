@@ -0,0 +1,309 @@
+//! Spectral Max-Cut heuristics.
+//!
+//! [`max_cut_spectral`] sorts the vertices by their entries in the eigenvector
+//! of the smallest eigenvalue of the adjacency matrix and takes the best cut
+//! between a prefix and the rest of this order. [`max_cut_trevisan`] is
+//! Trevisan's recursive spectral algorithm: the vertices with large entries in
+//! the smallest eigenvector of the normalized adjacency matrix are put on the
+//! side of their sign, the others are partitioned recursively. It cuts at least
+//! half of the edges.
+//!
+//! The eigenvectors are approximated by a power iteration on `s I - M` for the
+//! matrix `M` and a shift `s` moving the smallest eigenvalue of `M` to the
+//! largest one. It starts from a fixed vector, so all results are deterministic.
+
+use std::cmp::Reverse;
+
+use crate::graph::{Edge, Graph};
+use crate::parallel;
+
+/// Most matrix-vector products of a power iteration.
+pub const ITERATIONS: usize = 500;
+
+/// Distance of two consecutive unit vectors below which a power iteration stops.
+const TOLERANCE: f64 = 1e-8;
+
+/// Seed of the start vector of the power iterations.
+const START_SEED: u64 = 50;
+
+/// Unit eigenvector of the smallest eigenvalue of the adjacency matrix,
+/// approximated by at most [`ITERATIONS`] steps of a power iteration.
+pub fn smallest_eigenvector(graph: &Graph) -> Vec<f64> {
+    power_iteration(graph, false)
+}
+
+/// Best cut between the vertices with the smallest entries in the
+/// [`smallest_eigenvector`] and the others, runs in
+/// `O(ITERATIONS * (n + m) + n log n)`.
+pub fn max_cut_spectral(graph: &Graph) -> Vec<Edge> {
+    graph.cut(&sweep(graph, &smallest_eigenvector(graph)))
+}
+
+/// Trevisan's recursive spectral algorithm, cutting at least half of the edges.
+///
+/// Every round takes the vertices with an edge between them that are not
+/// placed yet. Sorted by the absolute value of their entries in the smallest
+/// eigenvector of the normalized adjacency matrix, the prefix is chosen that
+/// cuts the largest share of its inner edges and half of its edges to the other
+/// vertices. If that share is more than half, the prefix is placed on the
+/// sides of the signs of its entries and the next round goes on with the other
+/// vertices. Otherwise the rest is placed greedily. Finally, going back through
+/// the rounds, the vertices of all later rounds switch sides together if that
+/// cuts more of their edges to the prefix of the round.
+pub fn max_cut_trevisan(graph: &Graph) -> Vec<Edge> {
+    let n = graph.size();
+    let mut sides = vec![false; n];
+    let mut active = vec![true; n];
+    let mut rounds: Vec<Vec<usize>> = vec![];
+
+    loop {
+        let vertices: Vec<usize> = (0..n)
+            .filter(|vertex| active[*vertex] && graph.neighbors(*vertex).iter().any(|neigh| active[*neigh]))
+            .collect();
+
+        if vertices.is_empty() {
+            break;
+        }
+
+        // Later rounds often only have a few small components left
+        let subgraph = graph.induced_subgraph(&vertices);
+        let mut vector = power_iteration(&subgraph, true);
+        for (vertex, entry) in vector.iter_mut().enumerate() {
+            *entry /= (subgraph.neighbors(vertex).len() as f64).sqrt();
+        }
+
+        let Some(prefix) = best_prefix(&subgraph, &vector) else {
+            break;
+        };
+
+        for vertex in &prefix {
+            sides[vertices[*vertex]] = vector[*vertex] > 0.0;
+            active[vertices[*vertex]] = false;
+        }
+        rounds.push(prefix.iter().map(|vertex| vertices[*vertex]).collect());
+    }
+
+    // Every remaining vertex goes opposite to most of its placed neighbors
+    let mut placed = vec![false; n];
+    for vertex in (0..n).filter(|vertex| active[*vertex]) {
+        let (inside, outside): (Vec<usize>, Vec<usize>) = graph
+            .neighbors(vertex)
+            .iter()
+            .filter(|neigh| placed[**neigh])
+            .partition(|neigh| sides[**neigh]);
+        sides[vertex] = inside.len() < outside.len();
+        placed[vertex] = true;
+    }
+
+    let mut later = active;
+    for prefix in rounds.iter().rev() {
+        let (mut cut, mut uncut) = (0, 0);
+
+        for vertex in prefix {
+            for neigh in graph.neighbors(*vertex).iter().filter(|neigh| later[**neigh]) {
+                if sides[*neigh] != sides[*vertex] {
+                    cut += 1;
+                } else {
+                    uncut += 1;
+                }
+            }
+        }
+
+        if uncut > cut {
+            for vertex in (0..n).filter(|vertex| later[*vertex]) {
+                sides[vertex] = !sides[vertex];
+            }
+        }
+
+        for vertex in prefix {
+            later[*vertex] = true;
+        }
+    }
+
+    graph.cut(&sides)
+}
+
+/// Sides of the best cut between the vertices with the smallest entries in
+/// `vector` and the others.
+fn sweep(graph: &Graph, vector: &[f64]) -> Vec<bool> {
+    let mut order: Vec<usize> = (0..graph.size()).collect();
+    order.sort_by(|a, b| vector[*a].total_cmp(&vector[*b]));
+
+    let mut sides = vec![false; graph.size()];
+    let (mut value, mut best, mut best_len) = (0isize, 0, 0);
+
+    for (i, vertex) in order.iter().enumerate() {
+        let neighbors = graph.neighbors(*vertex);
+        let moved = neighbors.iter().filter(|neigh| sides[**neigh]).count() as isize;
+        // Edges to moved vertices become uncut, the others cut
+        value += neighbors.len() as isize - 2 * moved;
+        sides[*vertex] = true;
+
+        if value > best {
+            best = value;
+            best_len = i + 1;
+        }
+    }
+
+    sides.fill(false);
+    for vertex in &order[..best_len] {
+        sides[*vertex] = true;
+    }
+
+    sides
+}
+
+/// Vertices with the largest absolute entries in `vector`, whose signs cut the
+/// largest share of their inner edges and half of their edges to the other
+/// vertices. `None` if the share is at most half.
+fn best_prefix(graph: &Graph, vector: &[f64]) -> Option<Vec<usize>> {
+    let mut order: Vec<usize> = (0..graph.size())
+        .filter(|vertex| vector[*vertex] != 0.0)
+        .collect();
+    order.sort_by_key(|vertex| Reverse(vector[*vertex].abs().to_bits()));
+
+    let mut labeled = vec![false; graph.size()];
+    let (mut cut, mut uncut, mut crossing) = (0, 0, 0);
+    // Share `(2 cut + crossing) / (2 (cut + uncut + crossing))` of the best prefix
+    let mut best = (0, 1, 0);
+
+    for (i, vertex) in order.iter().enumerate() {
+        for neigh in graph.neighbors(*vertex) {
+            if !labeled[*neigh] {
+                crossing += 1;
+            } else {
+                crossing -= 1;
+
+                if (vector[*neigh] > 0.0) != (vector[*vertex] > 0.0) {
+                    cut += 1;
+                } else {
+                    uncut += 1;
+                }
+            }
+        }
+        labeled[*vertex] = true;
+
+        let (share, total) = (2 * cut + crossing, 2 * (cut + uncut + crossing));
+        if share * best.1 > best.0 * total {
+            best = (share, total, i + 1);
+        }
+    }
+
+    // More than half means more cut than uncut inner edges
+    (2 * best.0 > best.1).then(|| order[..best.2].to_vec())
+}
+
+/// Unit eigenvector of the smallest eigenvalue of the adjacency matrix,
+/// normalized by the degrees if `normalized`.
+fn power_iteration(graph: &Graph, normalized: bool) -> Vec<f64> {
+    let degrees = (0..graph.size()).map(|vertex| graph.neighbors(vertex).len());
+
+    // The eigenvalues of the normalized matrix lie in [-1, 1], the others in [-d, d]
+    let (shift, scale): (f64, Vec<f64>) = if normalized {
+        let scale = degrees
+            .map(|degree| match degree {
+                0 => 0.0,
+                _ => 1.0 / (degree as f64).sqrt(),
+            })
+            .collect();
+        (1.0, scale)
+    } else {
+        (degrees.max().unwrap_or(0) as f64, vec![1.0; graph.size()])
+    };
+
+    let mut x: Vec<f64> = (0..graph.size())
+        .map(|vertex| parallel::split_seed(START_SEED, vertex as u64) as f64 / u64::MAX as f64 - 0.5)
+        .collect();
+    normalize(&mut x);
+
+    for _ in 0..ITERATIONS {
+        let mut y: Vec<f64> = (0..graph.size())
+            .map(|vertex| {
+                let sum: f64 = graph
+                    .neighbors(vertex)
+                    .iter()
+                    .map(|neigh| scale[*neigh] * x[*neigh])
+                    .sum();
+                shift * x[vertex] - scale[vertex] * sum
+            })
+            .collect();
+
+        if !normalize(&mut y) {
+            break;
+        }
+
+        let change: f64 = x.iter().zip(&y).map(|(a, b)| (a - b) * (a - b)).sum();
+        x = y;
+
+        if change.sqrt() < TOLERANCE {
+            break;
+        }
+    }
+
+    x
+}
+
+/// Scales `x` to unit length, returns `false` if it is zero.
+fn normalize(x: &mut [f64]) -> bool {
+    let length = x.iter().map(|entry| entry * entry).sum::<f64>().sqrt();
+
+    if length == 0.0 {
+        return false;
+    }
+
+    for entry in x.iter_mut() {
+        *entry /= length;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+
+    #[test]
+    fn eigenvector_of_even_cycle() {
+        let mut graph = Graph::new_empty(6);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)]);
+
+        // The smallest eigenvalue -2 belongs to the alternating vector
+        let vector = smallest_eigenvector(&graph);
+        for (vertex, entry) in vector.iter().enumerate() {
+            assert!((entry.abs() - 1.0 / 6f64.sqrt()).abs() < 1e-6);
+            assert!(entry * vector[(vertex + 1) % 6] < 0.0);
+        }
+
+        assert_eq!(max_cut_spectral(&graph).len(), 6);
+        assert_eq!(max_cut_trevisan(&graph).len(), 6);
+    }
+
+    #[test]
+    fn bipartite_graphs_are_cut_completely() {
+        let mut graph = Graph::new_empty(9);
+        for u in 0..4 {
+            for v in 4..9 {
+                graph.add_edge(&(u, v));
+            }
+        }
+
+        assert_eq!(max_cut_spectral(&graph).len(), 20);
+        assert_eq!(max_cut_trevisan(&graph).len(), 20);
+    }
+
+    #[test]
+    fn trevisan_cuts_half() {
+        for seed in 0..10 {
+            let graph = generate::gnp(40, 0.05 + 0.05 * seed as f64, seed);
+            let cut = max_cut_trevisan(&graph);
+
+            assert_eq!(cut, graph.cut(&graph.partition(&cut).expect("not a cut")));
+            assert!(2 * cut.len() >= graph.edge_size(), "seed {seed}");
+            assert_eq!(cut, max_cut_trevisan(&graph));
+
+            let spectral = max_cut_spectral(&graph);
+            assert_eq!(spectral, graph.cut(&graph.partition(&spectral).expect("not a cut")));
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Structural statistics of Max-Cut instances.
+//!
+//! [`graph_stats`] summarizes sizes, degrees, components, triangles and an
+//! approximate diameter of a graph together with the trivial bounds for its
+//! maximum cut. Self-loops are never cut, so they count neither as edges nor
+//! towards the degrees. Edge weights are not parsed, every edge counts one.
+
+use std::fmt;
+
+use crate::bipartite;
+use crate::decompose;
+use crate::graph::Graph;
+
+/// Summary of the structure of a [`Graph`], see [`graph_stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphStats {
+    pub vertices: usize,
+    pub edges: usize,
+    /// Fraction of all possible edges present
+    pub density: f64,
+    /// `degrees[d]` is the number of vertices of degree `d`
+    pub degrees: Vec<usize>,
+    pub components: usize,
+    /// Number of components that aren't bipartite
+    pub odd_components: usize,
+    pub triangles: usize,
+    /// Lower bound for the diameter of the largest component
+    pub diameter: usize,
+    /// Poljak-Turzík lower bound for the maximum cut
+    pub lower_bound: usize,
+    /// Upper bound for the maximum cut: every non bipartite component
+    /// leaves at least one edge of an odd cycle uncut
+    pub upper_bound: usize,
+}
+
+impl GraphStats {
+    pub fn is_bipartite(&self) -> bool {
+        self.odd_components == 0
+    }
+
+    pub fn min_degree(&self) -> usize {
+        self.degrees.iter().position(|count| *count > 0).unwrap_or(0)
+    }
+
+    pub fn max_degree(&self) -> usize {
+        self.degrees.len().saturating_sub(1)
+    }
+
+    pub fn mean_degree(&self) -> f64 {
+        if self.vertices == 0 {
+            0.0
+        } else {
+            2.0 * self.edges as f64 / self.vertices as f64
+        }
+    }
+}
+
+impl fmt::Display for GraphStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "vertices: {}", self.vertices)?;
+        writeln!(f, "edges: {}", self.edges)?;
+        writeln!(f, "density: {:.6}", self.density)?;
+        writeln!(
+            f,
+            "degree: min {}, max {}, mean {:.3}",
+            self.min_degree(),
+            self.max_degree(),
+            self.mean_degree()
+        )?;
+        writeln!(f, "components: {}", self.components)?;
+        writeln!(f, "bipartite: {}", self.is_bipartite())?;
+        writeln!(f, "triangles: {}", self.triangles)?;
+        writeln!(f, "diameter: >= {}", self.diameter)?;
+        writeln!(f, "weights: not parsed")?;
+        writeln!(
+            f,
+            "maximum cut: >= {}, <= {}",
+            self.lower_bound, self.upper_bound
+        )?;
+        writeln!(f, "degree distribution:")?;
+
+        for (degree, count) in self.degrees.iter().enumerate() {
+            if *count > 0 {
+                writeln!(f, "  {degree}: {count}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Computes the [`GraphStats`] of `graph` in `O(m * sqrt(m))`.
+pub fn graph_stats(graph: &Graph) -> GraphStats {
+    let n = graph.size();
+    let m = graph.edge_size();
+
+    let mut degrees = vec![];
+    for vertex in 0..n {
+        let degree = graph.neighbors(vertex).iter().filter(|neigh| **neigh != vertex).count();
+        if degree >= degrees.len() {
+            degrees.resize(degree + 1, 0);
+        }
+        degrees[degree] += 1;
+    }
+
+    let components = decompose::connected_components(graph);
+    let colors = bipartite::two_coloring(graph);
+    let odd_components = components
+        .iter()
+        .filter(|component| !is_properly_colored(graph, component, &colors))
+        .count();
+
+    let diameter = components
+        .iter()
+        .max_by_key(|component| component.len())
+        .map_or(0, |component| double_sweep(graph, component[0]));
+
+    GraphStats {
+        vertices: n,
+        edges: m,
+        density: if n < 2 {
+            0.0
+        } else {
+            2.0 * m as f64 / (n as f64 * (n - 1) as f64)
+        },
+        degrees,
+        components: components.len(),
+        odd_components,
+        triangles: triangles(graph),
+        diameter,
+        lower_bound: (2 * m + n - components.len()).div_ceil(4),
+        upper_bound: m - odd_components,
+    }
+}
+
+/// Number of triangles in `graph`. Every edge is oriented towards the vertex
+/// of higher degree, so every vertex has at most `sqrt(2m)` out-neighbors.
+pub fn triangles(graph: &Graph) -> usize {
+    let rank = |v: usize| (graph.neighbors(v).len(), v);
+    let out: Vec<Vec<usize>> = (0..graph.size())
+        .map(|v| {
+            graph
+                .neighbors(v)
+                .iter()
+                .copied()
+                .filter(|u| rank(*u) > rank(v))
+                .collect()
+        })
+        .collect();
+
+    let mut marked = vec![false; graph.size()];
+    let mut count = 0;
+
+    for v in 0..graph.size() {
+        out[v].iter().for_each(|u| marked[*u] = true);
+
+        for u in &out[v] {
+            count += out[*u].iter().filter(|w| marked[**w]).count();
+        }
+
+        out[v].iter().for_each(|u| marked[*u] = false);
+    }
+
+    count
+}
+
+fn is_properly_colored(graph: &Graph, vertices: &[usize], colors: &[bool]) -> bool {
+    vertices.iter().all(|vertex| {
+        graph
+            .neighbors(*vertex)
+            .iter()
+            .all(|neigh| *neigh == *vertex || colors[*neigh] != colors[*vertex])
+    })
+}
+
+/// Lower bound for the diameter of the component of `start`: the eccentricity
+/// of the vertex farthest away from `start`.
+fn double_sweep(graph: &Graph, start: usize) -> usize {
+    let (farthest, _) = bfs_farthest(graph, start);
+    bfs_farthest(graph, farthest).1
+}
+
+fn bfs_farthest(graph: &Graph, start: usize) -> (usize, usize) {
+    let mut distance = vec![usize::MAX; graph.size()];
+    distance[start] = 0;
+    let mut queue = vec![start];
+    let mut i = 0;
+
+    while i < queue.len() {
+        let vertex = queue[i];
+        i += 1;
+
+        for neigh in graph.neighbors(vertex) {
+            if distance[*neigh] == usize::MAX {
+                distance[*neigh] = distance[vertex] + 1;
+                queue.push(*neigh);
+            }
+        }
+    }
+
+    let last = *queue.last().unwrap();
+    (last, distance[last])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wheel() {
+        // Hub 0 connected to the 5-cycle 1..=5, plus the separate edge (6, 7)
+        let mut graph = Graph::new_empty(8);
+        graph.add_edges(&[
+            (0, 1), (0, 2), (0, 3), (0, 4), (0, 5),
+            (1, 2), (2, 3), (3, 4), (4, 5), (5, 1),
+            (6, 7),
+        ]);
+
+        let stats = graph_stats(&graph);
+
+        assert_eq!(stats.vertices, 8);
+        assert_eq!(stats.edges, 11);
+        assert_eq!(stats.degrees, vec![0, 2, 0, 5, 0, 1]);
+        assert_eq!(stats.components, 2);
+        assert!(!stats.is_bipartite());
+        assert_eq!(stats.triangles, 5);
+        assert_eq!(stats.diameter, 2);
+        assert_eq!(stats.lower_bound, 7);
+        assert_eq!(stats.upper_bound, 10);
+    }
+
+    #[test]
+    fn even_cycle_is_bipartite() {
+        let mut graph = Graph::new_empty(6);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)]);
+
+        let stats = graph_stats(&graph);
+
+        assert!(stats.is_bipartite());
+        assert_eq!(stats.triangles, 0);
+        assert_eq!(stats.diameter, 3);
+        assert_eq!(stats.upper_bound, 6);
+    }
+
+    #[test]
+    fn self_loops_are_ignored() {
+        let mut graph = Graph::new_empty(4);
+        graph.add_edges(&[(0, 1), (1, 2), (2, 3), (0, 0), (2, 2)]);
+
+        let stats = graph_stats(&graph);
+
+        assert_eq!(stats.edges, 3);
+        assert_eq!(stats.degrees, vec![0, 2, 2]);
+        assert!(stats.is_bipartite());
+        assert_eq!(stats.upper_bound, 3);
+    }
+}
@@ -0,0 +1,424 @@
+//! Exact Max-Cut on graphs of small treewidth.
+//!
+//! A tree decomposition is built by eliminating the vertices one by one in a
+//! greedy order, every vertex forms a bag together with its neighbors at the
+//! time of its elimination. The dynamic program runs along the same order:
+//! for every side of the separator of a vertex it stores the best cut of the
+//! vertices eliminated below it, which takes `O(n * 2^w)` time and memory.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::fmt;
+
+use crate::graph::{Edge, Graph};
+
+/// Widest tree decomposition built, the dynamic program takes `O(2^w)` memory
+/// per bag. Larger values of `max_width` are lowered to it.
+pub const WIDTH_LIMIT: usize = 24;
+
+/// Greedy rule choosing the next vertex to eliminate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Elimination {
+    /// The vertex with the fewest neighbors
+    MinDegree,
+    /// The vertex whose elimination adds the fewest edges
+    MinFill,
+}
+
+/// Error indicating that no tree decomposition within the width limit was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WidthExceededError {
+    /// Width the elimination had reached when it was aborted
+    pub width: usize,
+}
+
+impl std::error::Error for WidthExceededError {}
+
+impl fmt::Display for WidthExceededError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Tree decomposition exceeds the width limit with width {}!", self.width)
+    }
+}
+
+/// Tree decomposition given by an elimination order. The bag of a vertex `v`
+/// is `v` with its separator, the parent bag is the one of the separator
+/// vertex eliminated first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeDecomposition {
+    /// The vertices in the order they were eliminated
+    pub order: Vec<usize>,
+    /// The neighbors of every vertex at the time of its elimination
+    pub separators: Vec<Vec<usize>>,
+    pub parent: Vec<Option<usize>>,
+}
+
+impl TreeDecomposition {
+    /// Eliminates the vertices of `graph` by the rule `elimination`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WidthExceededError`] as soon as a bag would get more than
+    /// `max_width + 1` vertices, or more than `WIDTH_LIMIT + 1`.
+    pub fn new(
+        graph: &Graph,
+        elimination: Elimination,
+        max_width: usize,
+    ) -> Result<TreeDecomposition, WidthExceededError> {
+        let max_width = max_width.min(WIDTH_LIMIT);
+        let n = graph.size();
+        // Self-loops are never cut and would put a vertex into its own separator
+        let mut neighbors: Vec<HashSet<usize>> = (0..n)
+            .map(|v| graph.neighbors(v).iter().copied().filter(|u| *u != v).collect())
+            .collect();
+
+        let key = |neighbors: &[HashSet<usize>], v: usize| {
+            let degree = neighbors[v].len();
+
+            match elimination {
+                Elimination::MinDegree => (degree, 0),
+                // Vertices over the limit can't be eliminated anyway
+                Elimination::MinFill if degree > max_width => (usize::MAX, degree),
+                Elimination::MinFill => (fill(neighbors, v), degree),
+            }
+        };
+
+        let mut keys: Vec<(usize, usize)> = (0..n).map(|v| key(&neighbors, v)).collect();
+        let mut queue: BinaryHeap<_> = (0..n).map(|v| Reverse((keys[v], v))).collect();
+        let mut eliminated = vec![false; n];
+        let mut position = vec![0; n];
+        let mut order = Vec::with_capacity(n);
+        let mut separators = vec![vec![]; n];
+
+        while let Some(Reverse((vertex_key, vertex))) = queue.pop() {
+            if eliminated[vertex] || keys[vertex] != vertex_key {
+                continue;
+            }
+
+            let separator: Vec<usize> = neighbors[vertex].iter().copied().collect();
+            if separator.len() > max_width {
+                return Err(WidthExceededError {
+                    width: separator.len(),
+                });
+            }
+
+            eliminated[vertex] = true;
+            position[vertex] = order.len();
+            order.push(vertex);
+
+            for (i, u) in separator.iter().enumerate() {
+                neighbors[*u].remove(&vertex);
+                for w in &separator[i + 1..] {
+                    neighbors[*u].insert(*w);
+                    neighbors[*w].insert(*u);
+                }
+            }
+
+            // The fill of the vertices next to the separator may change as
+            // well, the neighbors of vertices over the limit are skipped
+            let mut changed: HashSet<usize> = separator.iter().copied().collect();
+            if elimination == Elimination::MinFill {
+                for u in &separator {
+                    if neighbors[*u].len() <= max_width {
+                        changed.extend(&neighbors[*u]);
+                    }
+                }
+            }
+
+            for u in changed {
+                keys[u] = key(&neighbors, u);
+                queue.push(Reverse((keys[u], u)));
+            }
+
+            separators[vertex] = separator;
+        }
+
+        let parent = (0..n)
+            .map(|v| separators[v].iter().copied().min_by_key(|u| position[*u]))
+            .collect();
+
+        Ok(TreeDecomposition {
+            order,
+            separators,
+            parent,
+        })
+    }
+
+    /// Size of the largest bag minus one.
+    pub fn width(&self) -> usize {
+        self.separators.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    pub fn bag(&self, vertex: usize) -> Vec<usize> {
+        let mut bag = vec![vertex];
+        bag.extend(&self.separators[vertex]);
+        bag
+    }
+}
+
+/// A cut found with the help of a tree decomposition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreewidthCut {
+    pub cut: Vec<Edge>,
+    /// Width of the decomposition used, or the width reached before giving up
+    pub width: usize,
+    /// Whether the dynamic program ran, making `cut` maximum
+    pub exact: bool,
+}
+
+/// Returns the narrower of the min-degree and min-fill decompositions.
+///
+/// # Errors
+///
+/// Returns [`WidthExceededError`] if both exceed `max_width`.
+pub fn tree_decomposition(
+    graph: &Graph,
+    max_width: usize,
+) -> Result<TreeDecomposition, WidthExceededError> {
+    let min_degree = TreeDecomposition::new(graph, Elimination::MinDegree, max_width);
+    let limit = min_degree.as_ref().map_or(max_width, |d| d.width());
+    let min_fill = TreeDecomposition::new(graph, Elimination::MinFill, limit);
+
+    match (min_degree, min_fill) {
+        (_, Ok(decomposition)) | (Ok(decomposition), _) => Ok(decomposition),
+        (Err(a), Err(b)) => Err(WidthExceededError {
+            width: a.width.min(b.width),
+        }),
+    }
+}
+
+/// Calculates a maximum cut of `graph` if it has a tree decomposition of width
+/// at most `max_width`, otherwise the cut of `fallback` is returned.
+///
+/// # Errors
+///
+/// Returns the error of `fallback`.
+pub fn max_cut_treewidth<F, E>(graph: &Graph, max_width: usize, fallback: F) -> Result<TreewidthCut, E>
+where
+    F: FnOnce(&Graph) -> Result<Vec<Edge>, E>,
+{
+    match tree_decomposition(graph, max_width) {
+        Ok(decomposition) => Ok(TreewidthCut {
+            cut: max_cut_decomposition(graph, &decomposition),
+            width: decomposition.width(),
+            exact: true,
+        }),
+        Err(err) => Ok(TreewidthCut {
+            cut: fallback(graph)?,
+            width: err.width,
+            exact: false,
+        }),
+    }
+}
+
+/// Calculates a maximum cut of `graph` by dynamic programming over `decomposition`.
+///
+/// # Panics
+///
+/// Panics if `decomposition` is wider than [`WIDTH_LIMIT`].
+pub fn max_cut_decomposition(graph: &Graph, decomposition: &TreeDecomposition) -> Vec<Edge> {
+    assert!(decomposition.width() <= WIDTH_LIMIT, "tree decomposition too wide");
+    let n = graph.size();
+    let mut children = vec![vec![]; n];
+    for v in 0..n {
+        if let Some(parent) = decomposition.parent[v] {
+            children[parent].push(v);
+        }
+    }
+
+    // Bit of every vertex in the assignment of the current bag
+    let mut bit = vec![usize::MAX; n];
+    // Best cut below every vertex for every side of its separator
+    let mut best: Vec<Vec<usize>> = vec![vec![]; n];
+    // Side of every vertex in that cut
+    let mut choice: Vec<Vec<u64>> = vec![vec![]; n];
+
+    for vertex in &decomposition.order {
+        let separator = &decomposition.separators[*vertex];
+        let s = separator.len();
+
+        for (i, u) in separator.iter().enumerate() {
+            bit[*u] = i;
+        }
+        bit[*vertex] = s;
+
+        let edge_mask = graph
+            .neighbors(*vertex)
+            .iter()
+            .filter(|u| bit[**u] < s)
+            .fold(0usize, |mask, u| mask | 1 << bit[*u]);
+
+        let messages: Vec<(Vec<usize>, Vec<usize>)> = children[*vertex]
+            .iter()
+            .map(|child| {
+                let bits = decomposition.separators[*child].iter().map(|u| bit[*u]).collect();
+                (bits, std::mem::take(&mut best[*child]))
+            })
+            .collect();
+
+        let mut table = vec![0; 1 << s];
+        let mut sides = vec![0u64; (1usize << s).div_ceil(64)];
+
+        for (assignment, entry) in table.iter_mut().enumerate() {
+            let value = |side: usize| {
+                let full = assignment | side << s;
+                let cut = if side == 0 {
+                    assignment & edge_mask
+                } else {
+                    !assignment & edge_mask
+                };
+
+                messages.iter().fold(cut.count_ones() as usize, |sum, (bits, message)| {
+                    let index = bits
+                        .iter()
+                        .enumerate()
+                        .fold(0, |index, (i, b)| index | (full >> b & 1) << i);
+                    sum + message[index]
+                })
+            };
+
+            let (left, right) = (value(0), value(1));
+            *entry = left.max(right);
+            if right > left {
+                sides[assignment / 64] |= 1 << (assignment % 64);
+            }
+        }
+
+        best[*vertex] = table;
+        choice[*vertex] = sides;
+
+        for u in separator {
+            bit[*u] = usize::MAX;
+        }
+        bit[*vertex] = usize::MAX;
+    }
+
+    let mut sides = vec![false; n];
+    for vertex in decomposition.order.iter().rev() {
+        let assignment = decomposition.separators[*vertex]
+            .iter()
+            .enumerate()
+            .fold(0, |assignment, (i, u)| assignment | (sides[*u] as usize) << i);
+
+        sides[*vertex] = choice[*vertex][assignment / 64] >> (assignment % 64) & 1 == 1;
+    }
+
+    graph.cut(&sides)
+}
+
+/// Number of missing edges between the neighbors of `vertex`.
+fn fill(neighbors: &[HashSet<usize>], vertex: usize) -> usize {
+    let around: Vec<usize> = neighbors[vertex].iter().copied().collect();
+
+    around
+        .iter()
+        .enumerate()
+        .map(|(i, u)| {
+            around[i + 1..]
+                .iter()
+                .filter(|w| !neighbors[*u].contains(w))
+                .count()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brute_force;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use std::convert::Infallible;
+
+    #[test]
+    fn ladder_has_width_two() {
+        // 2 x 6 grid
+        let mut graph = Graph::new_empty(12);
+        for i in 0..6 {
+            graph.add_edge(&(i, i + 6));
+            if i < 5 {
+                graph.add_edges(&[(i, i + 1), (i + 6, i + 7)]);
+            }
+        }
+
+        for elimination in [Elimination::MinDegree, Elimination::MinFill] {
+            let decomposition = TreeDecomposition::new(&graph, elimination, 10).unwrap();
+            assert_eq!(decomposition.width(), 2);
+        }
+
+        let result = max_cut_treewidth(&graph, 2, |_| Ok::<_, Infallible>(vec![])).unwrap();
+
+        assert!(result.exact);
+        assert_eq!(result.cut.len(), 16);
+    }
+
+    #[test]
+    fn falls_back_above_limit() {
+        let mut k6 = Graph::new_empty(6);
+        for u in 0..6 {
+            for v in u + 1..6 {
+                k6.add_edge(&(u, v));
+            }
+        }
+
+        let result = max_cut_treewidth(&k6, 4, |_| Ok::<_, Infallible>(vec![])).unwrap();
+
+        assert!(!result.exact);
+        assert_eq!(result.width, 5);
+        assert!(result.cut.is_empty());
+
+        let result = max_cut_treewidth(&k6, 5, |_| Ok::<_, Infallible>(vec![])).unwrap();
+
+        assert!(result.exact);
+        assert_eq!(result.cut.len(), 9);
+
+        let mut k70 = Graph::new_empty(70);
+        for u in 0..70 {
+            for v in u + 1..70 {
+                k70.add_edge(&(u, v));
+            }
+        }
+
+        // Wider than the limit even with a larger `max_width`
+        let result = max_cut_treewidth(&k70, 80, |_| Ok::<_, Infallible>(vec![])).unwrap();
+        assert!(!result.exact);
+    }
+
+    #[test]
+    fn self_loops_are_ignored() {
+        // A 5-cycle looped at every vertex
+        let mut graph = Graph::new_empty(5);
+        for v in 0..5 {
+            graph.add_edges(&[(v, (v + 1) % 5), (v, v)]);
+        }
+
+        let result = max_cut_treewidth(&graph, 2, |_| Ok::<_, Infallible>(vec![])).unwrap();
+
+        assert!(result.exact);
+        assert_eq!(result.width, 2);
+        assert_eq!(result.cut.len(), 4);
+    }
+
+    #[test]
+    fn random_is_exact() {
+        let mut rng = SmallRng::seed_from_u64(31);
+
+        for _ in 0..100 {
+            let n = rng.gen_range(1..13);
+            let mut graph = Graph::new_empty(n);
+
+            for u in 0..n {
+                for v in u + 1..n {
+                    if rng.gen_bool(0.3) {
+                        graph.add_edge(&(u, v));
+                    }
+                }
+            }
+
+            let decomposition = tree_decomposition(&graph, n).unwrap();
+            let cut = max_cut_decomposition(&graph, &decomposition);
+
+            assert!(graph.partition(&cut).is_some());
+            let best = brute_force::max_cut_brute_force(&graph, 20).unwrap();
+            assert_eq!(cut.len(), best.len());
+        }
+    }
+}
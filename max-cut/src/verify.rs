@@ -0,0 +1,168 @@
+//! Differential verification of the solvers on seeded random graphs.
+//!
+//! Every cut returned has to be induced by a bipartition, the exact solvers
+//! have to agree with each other and every cut has to respect the bounds known
+//! for the graph and the guarantees of its algorithm.
+
+use std::fmt;
+
+use crate::generate;
+use crate::graph::{Edge, Graph};
+use crate::solver::Algorithm;
+use crate::stats;
+
+/// A violated expectation of an algorithm on a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    /// Seed of the generated graph
+    pub seed: u64,
+    pub algorithm: Algorithm,
+    pub message: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seed {}, {}: {}", self.seed, self.algorithm, self.message)
+    }
+}
+
+/// Checks that `cut` is exactly the set of edges induced by a bipartition of
+/// `graph` and returns that bipartition.
+///
+/// # Errors
+///
+/// Returns a description of the first problem found.
+pub fn check_cut(graph: &Graph, cut: &[Edge]) -> Result<Vec<bool>, String> {
+    let Some(sides) = graph.partition(cut) else {
+        return Err("cut is not induced by a bipartition".to_owned());
+    };
+
+    let value = graph.cut(&sides).len();
+    if value != cut.len() {
+        return Err(format!(
+            "cut has {} edges but its bipartition cuts {value}",
+            cut.len()
+        ));
+    }
+
+    Ok(sides)
+}
+
+/// Runs every algorithm on `graph` and returns the violated expectations
+/// together with the failing algorithm.
+pub fn verify_graph(graph: &Graph, algorithms: &[Algorithm]) -> Vec<(Algorithm, String)> {
+    let stats = stats::graph_stats(graph);
+    let mut failures = vec![];
+    let mut exact: Option<(Algorithm, usize)> = None;
+    let mut values = vec![];
+
+    for algorithm in algorithms {
+        let solution = match algorithm.solve(graph) {
+            Ok(solution) => solution,
+            Err(err) if err.is_inapplicable() => continue,
+            Err(err) => {
+                failures.push((*algorithm, err.to_string()));
+                continue;
+            }
+        };
+
+        if let Err(message) = check_cut(graph, &solution.cut) {
+            failures.push((*algorithm, message));
+            continue;
+        }
+
+        let value = solution.cut.len();
+
+        if value > stats.upper_bound {
+            failures.push((
+                *algorithm,
+                format!("value {value} above the upper bound {}", stats.upper_bound),
+            ));
+        }
+
+        if algorithm.is_half_approximation() && 2 * value < graph.edge_size() {
+            failures.push((
+                *algorithm,
+                format!("value {value} below half of {} edges", graph.edge_size()),
+            ));
+        }
+
+        if solution.exact {
+            if value < stats.lower_bound {
+                failures.push((
+                    *algorithm,
+                    format!("value {value} below the lower bound {}", stats.lower_bound),
+                ));
+            }
+
+            match exact {
+                Some((other, best)) if best != value => failures.push((
+                    *algorithm,
+                    format!("exact value {value} differs from {best} of {other}"),
+                )),
+                Some(_) => {}
+                None => exact = Some((*algorithm, value)),
+            }
+        }
+
+        values.push((*algorithm, value));
+    }
+
+    if let Some((other, best)) = exact {
+        for (algorithm, value) in values {
+            if value > best {
+                failures.push((
+                    algorithm,
+                    format!("value {value} above the maximum {best} of {other}"),
+                ));
+            }
+        }
+    }
+
+    failures
+}
+
+/// Verifies `algorithms` on `count` random graphs with up to `max_size`
+/// vertices. The `i`-th graph is generated from the seed `seed + i`.
+pub fn verify_random(seed: u64, count: u64, max_size: usize, algorithms: &[Algorithm]) -> Vec<Failure> {
+    (seed..seed + count)
+        .flat_map(|seed| {
+            let graph = random_graph(seed, max_size);
+
+            verify_graph(&graph, algorithms)
+                .into_iter()
+                .map(move |(algorithm, message)| Failure {
+                    seed,
+                    algorithm,
+                    message,
+                })
+        })
+        .collect()
+}
+
+/// Graph of the size and density given by `seed`, every fourth one is sparse
+/// enough to be mostly planar and of small treewidth.
+pub fn random_graph(seed: u64, max_size: usize) -> Graph {
+    let n = (seed as usize * 7919) % max_size + 1;
+
+    if seed.is_multiple_of(4) {
+        generate::gnm(n, n + n / 2, seed)
+    } else {
+        generate::gnp(n, [0.2, 0.4, 0.7][seed as usize % 3], seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_cuts() {
+        let mut graph = Graph::new_empty(3);
+        graph.add_edges(&[(0, 1), (1, 2), (0, 2)]);
+
+        assert_eq!(check_cut(&graph, &[Edge(0, 1), Edge(1, 2)]), Ok(vec![false, true, false]));
+        assert!(check_cut(&graph, &[Edge(0, 1), Edge(1, 2), Edge(0, 2)]).is_err());
+        assert!(check_cut(&graph, &[Edge(0, 1), Edge(0, 2), Edge(1, 0)]).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+//! Drawings of a graph together with a cut.
+//!
+//! Vertices are colored by their side and cut edges are drawn highlighted.
+//! [`to_dot`] leaves the layout to Graphviz, [`to_svg`] places the vertices
+//! itself with a force directed layout, which is only practical for small
+//! graphs.
+
+use std::fmt::Write;
+
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::graph::{Edge, Graph};
+
+const SIDE_COLORS: [&str; 2] = ["#4c72b0", "#dd8452"];
+const CUT_COLOR: &str = "#c44e52";
+const UNCUT_COLOR: &str = "#b0b0b0";
+
+/// Graphviz DOT description of `graph` with the cut `cut`.
+/// Returns `None` if no bipartition induces `cut`.
+pub fn to_dot(graph: &Graph, cut: &[Edge]) -> Option<String> {
+    let sides = graph.partition(cut)?;
+    let mut dot = String::new();
+
+    writeln!(dot, "graph maxcut {{").unwrap();
+    writeln!(dot, "  label=\"cut: {} of {} edges\";", cut.len(), graph.edge_size()).unwrap();
+    writeln!(dot, "  node [style=filled, fontcolor=white];").unwrap();
+
+    for (vertex, side) in sides.iter().enumerate() {
+        writeln!(
+            dot,
+            "  {} [fillcolor=\"{}\"];",
+            vertex + 1,
+            SIDE_COLORS[*side as usize]
+        )
+        .unwrap();
+    }
+
+    for edge in graph.all_edges() {
+        let style = if sides[edge.0] != sides[edge.1] {
+            format!("color=\"{CUT_COLOR}\", penwidth=2")
+        } else {
+            format!("color=\"{UNCUT_COLOR}\", style=dashed")
+        };
+
+        writeln!(dot, "  {} -- {} [{style}];", edge.0 + 1, edge.1 + 1).unwrap();
+    }
+
+    writeln!(dot, "}}").unwrap();
+    Some(dot)
+}
+
+/// SVG drawing of `graph` with the cut `cut`, laid out by [`layout`].
+/// Returns `None` if no bipartition induces `cut`.
+pub fn to_svg(graph: &Graph, cut: &[Edge], seed: u64) -> Option<String> {
+    const SIZE: f64 = 800.0;
+    const MARGIN: f64 = 30.0;
+    const RADIUS: f64 = 10.0;
+
+    let sides = graph.partition(cut)?;
+    let positions = layout(graph, seed, 300);
+
+    // Scale the layout into the drawing area
+    let (min_x, max_x, min_y, max_y) = positions.iter().fold(
+        (f64::MAX, f64::MIN, f64::MAX, f64::MIN),
+        |(min_x, max_x, min_y, max_y), (x, y)| (min_x.min(*x), max_x.max(*x), min_y.min(*y), max_y.max(*y)),
+    );
+    let scale = (SIZE - 2.0 * MARGIN) / (max_x - min_x).max(max_y - min_y).max(f64::EPSILON);
+    let position = |v: usize| {
+        (
+            MARGIN + (positions[v].0 - min_x) * scale,
+            MARGIN + (positions[v].1 - min_y) * scale,
+        )
+    };
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{SIZE}\" height=\"{SIZE}\" viewBox=\"0 0 {SIZE} {SIZE}\">"
+    )
+    .unwrap();
+    writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>").unwrap();
+
+    for edge in graph.all_edges() {
+        let ((x1, y1), (x2, y2)) = (position(edge.0), position(edge.1));
+        let style = if sides[edge.0] != sides[edge.1] {
+            format!("stroke=\"{CUT_COLOR}\" stroke-width=\"2\"")
+        } else {
+            format!("stroke=\"{UNCUT_COLOR}\" stroke-width=\"1\" stroke-dasharray=\"4 3\"")
+        };
+
+        writeln!(
+            svg,
+            "<line x1=\"{x1:.1}\" y1=\"{y1:.1}\" x2=\"{x2:.1}\" y2=\"{y2:.1}\" {style}/>"
+        )
+        .unwrap();
+    }
+
+    for (vertex, side) in sides.iter().enumerate() {
+        let (x, y) = position(vertex);
+
+        writeln!(
+            svg,
+            "<circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"{RADIUS}\" fill=\"{}\"/>",
+            SIDE_COLORS[*side as usize]
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<text x=\"{x:.1}\" y=\"{:.1}\" font-size=\"10\" text-anchor=\"middle\" fill=\"white\">{}</text>",
+            y + 3.5,
+            vertex + 1
+        )
+        .unwrap();
+    }
+
+    writeln!(svg, "</svg>").unwrap();
+    Some(svg)
+}
+
+/// Fruchterman-Reingold layout around the unit square starting from random
+/// positions given by `seed`. Takes `O(n^2)` per iteration.
+pub fn layout(graph: &Graph, seed: u64, iterations: usize) -> Vec<(f64, f64)> {
+    let n = graph.size();
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut positions: Vec<(f64, f64)> = (0..n).map(|_| (rng.gen(), rng.gen())).collect();
+
+    if n < 2 {
+        return positions;
+    }
+
+    let k = (1.0 / n as f64).sqrt();
+    let edges = graph.all_edges();
+
+    for iteration in 0..iterations {
+        let temperature = 0.1 * (1.0 - iteration as f64 / iterations as f64);
+        let mut forces = vec![(0.0, 0.0); n];
+
+        for u in 0..n {
+            for v in u + 1..n {
+                let (dx, dy) = (positions[u].0 - positions[v].0, positions[u].1 - positions[v].1);
+                let distance = (dx * dx + dy * dy).sqrt().max(1e-4);
+                let repulsion = k * k / distance;
+
+                forces[u].0 += dx / distance * repulsion;
+                forces[u].1 += dy / distance * repulsion;
+                forces[v].0 -= dx / distance * repulsion;
+                forces[v].1 -= dy / distance * repulsion;
+            }
+        }
+
+        for edge in &edges {
+            let (u, v) = (edge.0, edge.1);
+            let (dx, dy) = (positions[u].0 - positions[v].0, positions[u].1 - positions[v].1);
+            let distance = (dx * dx + dy * dy).sqrt().max(1e-4);
+            let attraction = distance * distance / k;
+
+            forces[u].0 -= dx / distance * attraction;
+            forces[u].1 -= dy / distance * attraction;
+            forces[v].0 += dx / distance * attraction;
+            forces[v].1 += dy / distance * attraction;
+        }
+
+        for (position, mut force) in positions.iter_mut().zip(forces) {
+            // Weak pull to the center keeps components together
+            force.0 -= (position.0 - 0.5) * k;
+            force.1 -= (position.1 - 0.5) * k;
+
+            let length = (force.0 * force.0 + force.1 * force.1).sqrt().max(1e-9);
+            let step = length.min(temperature);
+
+            position.0 += force.0 / length * step;
+            position.1 += force.1 / length * step;
+        }
+    }
+
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dot_and_svg() {
+        let mut graph = Graph::new_empty(3);
+        graph.add_edges(&[(0, 1), (1, 2), (0, 2)]);
+        let cut = [Edge(0, 1), Edge(1, 2)];
+
+        let dot = to_dot(&graph, &cut).unwrap();
+        let svg = to_svg(&graph, &cut, 36).unwrap();
+
+        assert_eq!(dot.matches("penwidth").count(), 2);
+        assert_eq!(dot.matches("dashed").count(), 1);
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert_eq!(svg.matches("<line").count(), 3);
+        assert_eq!(svg, to_svg(&graph, &cut, 36).unwrap());
+        assert!(to_dot(&graph, &[Edge(0, 1)]).is_none());
+    }
+}
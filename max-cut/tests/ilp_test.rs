@@ -1,8 +1,8 @@
 use std::process;
 use std::fs;
 
-use good_lp::Solution;
 use max_cut::*;
+use max_cut::solver::Algorithm;
 
 pub fn setup() {
 }
@@ -20,8 +20,24 @@ fn ilp_test() -> Result<(), good_lp::ResolutionError> {
     });
 
     let ilp = ilp::MaxCutIlp::new(&graph);
+    let cut = ilp.solve()?;
+    let best = brute_force::max_cut_brute_force(&graph, 20).unwrap();
 
-    println!("{:?}", ilp.solve()?);
+    assert!(verify::check_cut(&graph, &cut).is_ok());
+    assert_eq!(cut.len(), best.len());
     
     Ok(())
 }
+
+#[test]
+fn ilp_agrees_with_brute_force() {
+    let algorithms = [Algorithm::BruteForce { max_size: 16 }, Algorithm::Ilp];
+
+    let failures = verify::verify_random(1000, 50, 12, &algorithms);
+
+    for failure in &failures {
+        eprintln!("{failure}");
+    }
+
+    assert!(failures.is_empty());
+}
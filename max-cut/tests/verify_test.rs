@@ -0,0 +1,37 @@
+use max_cut::approx::VertexOrder;
+use max_cut::solver::Algorithm;
+use max_cut::verify;
+
+#[test]
+fn solvers_agree() {
+    let algorithms = [
+        Algorithm::BruteForce { max_size: 16 },
+        Algorithm::Planar,
+        Algorithm::Treewidth { max_width: 16 },
+        Algorithm::Oct { max_exact: 16 },
+        Algorithm::Greedy,
+        Algorithm::GreedyImproved,
+        Algorithm::GreedyOrdered {
+            order: VertexOrder::SmallestLast,
+            passes: 2,
+        },
+        Algorithm::GreedyOrdered {
+            order: VertexOrder::Random,
+            passes: 0,
+        },
+        Algorithm::Random,
+        Algorithm::RandomImproved,
+        Algorithm::RandomParallel,
+        Algorithm::Derandomized,
+        Algorithm::Spectral,
+        Algorithm::Trevisan,
+    ];
+
+    let failures = verify::verify_random(0, 300, 14, &algorithms);
+
+    for failure in &failures {
+        eprintln!("{failure}");
+    }
+
+    assert!(failures.is_empty());
+}